@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! ECDSA signature encoding
+//!
+//! Quotes carry ECDSA P-256 signatures as the raw concatenation of `r` and
+//! `s`, while most cryptography libraries expect them DER-encoded. This
+//! module converts between the two without allocating.
+
+/// A DER-encoded ECDSA P-256 signature
+///
+/// The encoding is an ASN.1 `SEQUENCE` of two `INTEGER`s and never exceeds
+/// 72 bytes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DerSignature {
+    buf: [u8; Self::MAX_LEN],
+    len: usize,
+}
+
+impl DerSignature {
+    /// The maximum length of an encoded signature
+    pub const MAX_LEN: usize = 72;
+
+    /// Encodes a raw `r || s` signature (32 big-endian bytes each).
+    pub fn from_raw(raw: &[u8; 64]) -> Self {
+        let mut buf = [0u8; Self::MAX_LEN];
+        let mut len = 2;
+
+        for int in raw.chunks(32) {
+            // Strip leading zeroes, but keep at least one byte.
+            let skip = int.iter().take(31).take_while(|b| **b == 0).count();
+            let int = &int[skip..];
+            let pad = int[0] >> 7;
+
+            buf[len] = 0x02;
+            buf[len + 1] = int.len() as u8 + pad;
+            len += 2 + pad as usize;
+            buf[len..][..int.len()].copy_from_slice(int);
+            len += int.len();
+        }
+
+        buf[0] = 0x30;
+        buf[1] = len as u8 - 2;
+        Self { buf, len }
+    }
+}
+
+impl From<&[u8; 64]> for DerSignature {
+    #[inline]
+    fn from(raw: &[u8; 64]) -> Self {
+        Self::from_raw(raw)
+    }
+}
+
+impl AsRef<[u8]> for DerSignature {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DerSignature;
+
+    #[test]
+    fn encode() {
+        let mut raw = [0u8; 64];
+        let der = DerSignature::from_raw(&raw);
+        assert_eq!(der.as_ref(), [0x30, 6, 2, 1, 0, 2, 1, 0]);
+
+        raw[31] = 0x7f;
+        raw[32] = 0x80;
+        let der = DerSignature::from_raw(&raw);
+        assert_eq!(der.as_ref()[..5], [0x30, 38, 2, 1, 0x7f]);
+        assert_eq!(der.as_ref()[5..9], [2, 33, 0, 0x80]);
+        assert_eq!(der.as_ref().len(), 40);
+
+        let raw = [0xff; 64];
+        let der = DerSignature::from(&raw);
+        assert_eq!(der.as_ref().len(), DerSignature::MAX_LEN);
+        assert_eq!(der.as_ref()[..5], [0x30, 70, 2, 33, 0]);
+        assert_eq!(der.as_ref()[37..40], [2, 33, 0]);
+    }
+}
@@ -5,6 +5,8 @@
 //! This module contains traits for implementing cryptography. It also contains
 //! some cryptography backends (see the `openssl` and `rcrypto` crate features).
 
+pub mod ecdsa;
+
 #[cfg(feature = "openssl")]
 pub mod openssl;
 
@@ -26,6 +28,42 @@ pub trait Digest: Sized {
     }
 }
 
+/// AES-128-CMAC message authentication
+///
+/// This is the MAC used by the hardware to authenticate a `Report`.
+pub trait Cmac: Sized {
+    type Error: core::fmt::Debug;
+
+    fn new(key: &[u8; 16]) -> Result<Self, Self::Error>;
+    fn mac(&self, bytes: &[u8]) -> Result<[u8; 16], Self::Error>;
+
+    /// Checks `tag` against the MAC of `bytes` in constant time
+    #[inline]
+    fn verify(&self, bytes: &[u8], tag: &[u8; 16]) -> Result<bool, Self::Error> {
+        let mac = self.mac(bytes)?;
+        Ok(mac.iter().zip(tag).fold(0, |acc, (l, r)| acc | (l ^ r)) == 0)
+    }
+}
+
+/// AES-128-GCM authenticated encryption
+///
+/// This is the cipher typically used to seal enclave data. Both operations
+/// work in place on `data` and use a detached 16-byte tag. If decryption
+/// fails, `data` never holds the unauthenticated plaintext.
+pub trait Aead: Sized {
+    type Error: core::fmt::Debug;
+
+    fn new(key: &[u8; 16]) -> Result<Self, Self::Error>;
+    fn encrypt(&self, iv: &[u8; 12], aad: &[u8], data: &mut [u8]) -> Result<[u8; 16], Self::Error>;
+    fn decrypt(
+        &self,
+        iv: &[u8; 12],
+        aad: &[u8],
+        data: &mut [u8],
+        tag: &[u8; 16],
+    ) -> Result<(), Self::Error>;
+}
+
 /// A private key used for signing an enclave
 pub trait PrivateKey: Sized {
     type Error: core::fmt::Debug;
@@ -36,6 +74,74 @@ pub trait PrivateKey: Sized {
     fn sign(&self, author: &[u8], body: &[u8]) -> Result<SigData, Self::Error>;
 }
 
+/// A private key used for RSA-OAEP decryption
+///
+/// Both OAEP and MGF1 use SHA2-256, as the PCE does when it encrypts the PPID
+/// with the provisioning key (see `PpidInfo`).
+pub trait OaepDecrypt {
+    type Error: core::fmt::Debug;
+
+    /// Decrypts a big-endian `ciphertext` into `plaintext`
+    ///
+    /// Returns the length of the plaintext. Fails if `plaintext` is too short.
+    fn decrypt(&self, ciphertext: &[u8], plaintext: &mut [u8]) -> Result<usize, Self::Error>;
+}
+
+/// A public key used for assembling an enclave signature
+pub trait PublicKey: Sized {
+    type Error: core::fmt::Debug;
+
+    /// Creates a key from the little-endian modulus and the exponent
+    fn from_parts(modulus: &[u8; 384], exponent: u32) -> Result<Self, Self::Error>;
+
+    /// Creates a key from a PEM-encoded `SubjectPublicKeyInfo`
+    ///
+    /// Keys with a modulus other than 3072 bits are rejected.
+    fn from_pem(pem: &str) -> Result<Self, Self::Error>;
+
+    /// Creates a key from a DER-encoded `SubjectPublicKeyInfo`
+    ///
+    /// Keys with a modulus other than 3072 bits are rejected.
+    fn from_der(der: &[u8]) -> Result<Self, Self::Error>;
+
+    /// The little-endian modulus, as stored in `SIGSTRUCT`
+    fn modulus(&self) -> [u8; 384];
+
+    /// Computes the `MRSIGNER` value for this key
+    ///
+    /// With SHA2-256, the result can be compared to `ReportBody::mrsigner`.
+    #[inline]
+    fn mrsigner<D: Digest>(&self) -> D::Output {
+        D::new().chain(&self.modulus()).finish()
+    }
+
+    /// Completes a detached signature
+    ///
+    /// This computes `q1` and `q2` for the little-endian RSA `signature`
+    /// produced by the corresponding private key.
+    fn sig_data(&self, signature: &[u8; 384]) -> Result<SigData, Self::Error>;
+
+    /// Checks the little-endian RSA `signature` over `author` and `body`
+    fn verify(
+        &self,
+        author: &[u8],
+        body: &[u8],
+        signature: &[u8; 384],
+    ) -> Result<bool, Self::Error>;
+
+    /// Checks the `q1` and `q2` values that `EINIT` uses to verify `signature`
+    #[inline]
+    fn verify_q1_q2(
+        &self,
+        signature: &[u8; 384],
+        q1: &[u8; 384],
+        q2: &[u8; 384],
+    ) -> Result<bool, Self::Error> {
+        let sd = self.sig_data(signature)?;
+        Ok(sd.q1 == *q1 && sd.q2 == *q2)
+    }
+}
+
 /// A detached enclave signature
 pub struct SigData {
     pub signature: [u8; 384],
@@ -45,15 +151,35 @@ pub struct SigData {
     pub q2: [u8; 384],
 }
 
+impl SigData {
+    /// Computes the `MRSIGNER` value for the signing key
+    ///
+    /// This is the hash of the little-endian modulus. Use SHA2-256 to get the
+    /// value reported in `ReportBody::mrsigner`.
+    pub fn mrsigner<D: Digest>(&self) -> D::Output {
+        D::new().chain(&self.modulus).finish()
+    }
+}
+
 #[cfg(test)]
 #[allow(dead_code)]
-fn selftest<K: PrivateKey, D: Digest<Output = [u8; 32]>>() {
+fn selftest<K: PrivateKey + OaepDecrypt, P: PublicKey, D: Digest<Output = [u8; 32]>>() {
     const SIG: &[u8; size_of::<Signature>()] = include_bytes!("../../tests/encl.ss");
     const BIN: &[u8] = include_bytes!("../../tests/encl.bin");
     const PEM: &str = include_str!("../../tests/encl.pem");
+    const PUB_PEM: &str = include_str!("../../tests/encl.pub.pem");
+    const PUB_DER: &[u8] = include_bytes!("../../tests/encl.pub.der");
+    const PUB_4096: &str = include_str!("../../tests/rsa4096.pub.pem");
+    const PUB_2048: &[u8] = include_bytes!("../../tests/rsa2048.pub.der");
+    const OAEP: &[u8; 384] = include_bytes!("../../tests/ppid.oaep");
     const PAGE: usize = 4096;
+    const MRSIGNER: [u8; 32] = [
+        0x2f, 0x9f, 0x8f, 0xd4, 0xfe, 0x12, 0xd7, 0x72, 0x32, 0xf1, 0xd8, 0x75, 0x71, 0xca, 0x82,
+        0x52, 0xca, 0x27, 0x71, 0x4e, 0xfe, 0x77, 0x05, 0xe4, 0x62, 0x22, 0xcf, 0xfd, 0x5a, 0x22,
+        0xe8, 0xc4,
+    ];
 
-    use core::mem::{size_of, transmute};
+    use core::mem::size_of;
     use core::num::NonZeroU32;
 
     use crate::page::{Class, Flags, SecInfo};
@@ -61,7 +187,7 @@ fn selftest<K: PrivateKey, D: Digest<Output = [u8; 32]>>() {
     use crate::signature::{Author, Hasher, Signature};
 
     let len = BIN.len().next_power_of_two();
-    let sig: Signature = unsafe { transmute(*SIG) };
+    let sig = Signature::from(*SIG);
     let rwx = Flags::READ | Flags::WRITE | Flags::EXECUTE;
 
     // Validate hash generation
@@ -90,4 +216,102 @@ fn selftest<K: PrivateKey, D: Digest<Output = [u8; 32]>>() {
     let key = K::from_pem(PEM).unwrap();
     assert_eq!(sig, Signature::new(&key, sig.author(), sig.body()).unwrap());
     assert_eq!(sig, Signature::new(&key, Author::new(0, 0), body).unwrap());
+
+    // Validate RSA-OAEP decryption
+    let mut plaintext = [0u8; 16];
+    let len = key.decrypt(OAEP, &mut plaintext).unwrap();
+    assert_eq!(len, 16);
+    assert_eq!(
+        plaintext,
+        0x0011_2233_4455_6677_8899_aabb_ccdd_eeffu128.to_be_bytes()
+    );
+    assert!(key.decrypt(OAEP, &mut plaintext[..15]).is_err());
+    let mut tampered = *OAEP;
+    tampered[100] ^= 1;
+    assert!(key.decrypt(&tampered, &mut plaintext).is_err());
+
+    // Validate signature assembly from an external signature
+    let (modulus, exponent) = (sig.modulus(), sig.exponent());
+    let assembled =
+        Signature::from_parts::<P>(sig.author(), sig.body(), modulus, exponent, sig.signature());
+    assert_eq!(sig, assembled.unwrap());
+
+    // Validate signature verification
+    assert!(sig.verify::<P>().unwrap());
+    let mut bytes: [u8; size_of::<Signature>()] = sig.clone().into();
+    for offset in [0, 900, 1040, 1424] {
+        bytes[offset] ^= 1;
+        assert!(!Signature::from(bytes).verify::<P>().unwrap());
+        bytes[offset] ^= 1;
+    }
+
+    // Validate signer measurement
+    let public = P::from_parts(modulus, exponent).unwrap();
+    let sd = public.sig_data(sig.signature()).unwrap();
+    assert_eq!(sd.mrsigner::<D>(), MRSIGNER);
+    assert_eq!(sig.mrsigner::<D>(), MRSIGNER);
+
+    // Validate signer measurement from the public key alone
+    assert_eq!(public.mrsigner::<D>(), MRSIGNER);
+    assert_eq!(P::from_pem(PUB_PEM).unwrap().mrsigner::<D>(), MRSIGNER);
+    assert_eq!(P::from_der(PUB_DER).unwrap().mrsigner::<D>(), MRSIGNER);
+    assert!(P::from_pem(PEM).is_err());
+    assert!(P::from_pem(PUB_4096).is_err());
+    assert!(P::from_der(PUB_2048).is_err());
+
+    // Validate q1 and q2
+    assert!(public
+        .verify_q1_q2(sig.signature(), &sd.q1, &sd.q2)
+        .unwrap());
+    assert!(!public
+        .verify_q1_q2(sig.signature(), &sd.q2, &sd.q1)
+        .unwrap());
+}
+
+#[cfg(test)]
+#[allow(dead_code)]
+fn cmac_selftest<C: Cmac>() {
+    // RFC 4493, Section 4
+    const KEY: [u8; 16] = 0x2b7e151628aed2a6abf7158809cf4f3cu128.to_be_bytes();
+    const MSG: [u8; 16] = 0x6bc1bee22e409f96e93d7e117393172au128.to_be_bytes();
+    const EMPTY: [u8; 16] = 0xbb1d6929e95937287fa37d129b756746u128.to_be_bytes();
+    const BLOCK: [u8; 16] = 0x070a16b46b4d4144f79bdd9dd04a287cu128.to_be_bytes();
+
+    let cmac = C::new(&KEY).unwrap();
+    assert_eq!(cmac.mac(&[]).unwrap(), EMPTY);
+    assert_eq!(cmac.mac(&MSG).unwrap(), BLOCK);
+    assert!(cmac.verify(&MSG, &BLOCK).unwrap());
+    assert!(!cmac.verify(&MSG, &EMPTY).unwrap());
+}
+
+#[cfg(test)]
+#[allow(dead_code)]
+fn aead_selftest<A: Aead>() {
+    // NIST GCM specification, Test Case 2
+    const CT: [u8; 16] = 0x0388dace60b6a392f328c2b971b2fe78u128.to_be_bytes();
+    const TAG: [u8; 16] = 0xab6e47d42cec13bdf53a67b21257bddfu128.to_be_bytes();
+
+    let aead = A::new(&[0; 16]).unwrap();
+    let mut data = [0u8; 16];
+    assert_eq!(aead.encrypt(&[0; 12], &[], &mut data).unwrap(), TAG);
+    assert_eq!(data, CT);
+
+    aead.decrypt(&[0; 12], &[], &mut data, &TAG).unwrap();
+    assert_eq!(data, [0; 16]);
+
+    let mut data = CT;
+    assert!(aead.decrypt(&[0; 12], b"aad", &mut data, &TAG).is_err());
+    assert!(data == CT || data == [0; 16]);
+
+    // A forged tag must not leak the plaintext.
+    let mut data = *b"sealed enclave secret";
+    let tag = aead.encrypt(&[1; 12], &[], &mut data).unwrap();
+    let ct = data;
+    let mut forged = tag;
+    forged[0] ^= 1;
+    assert!(aead.decrypt(&[1; 12], &[], &mut data, &forged).is_err());
+    assert!(data == ct || data == [0; 21]);
+    let mut data = ct;
+    aead.decrypt(&[1; 12], &[], &mut data, &tag).unwrap();
+    assert_eq!(&data, b"sealed enclave secret");
 }
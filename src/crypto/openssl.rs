@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use openssl::{bn, error::ErrorStack, pkey, rsa, sha, sign};
+use openssl::{bn, encrypt, error::ErrorStack, pkey, rsa, sha, sign, symm};
 
 fn arr_from_bn(value: &bn::BigNumRef) -> [u8; 384] {
     let mut le = [0u8; 384];
@@ -14,6 +14,40 @@ fn arr_from_bn(value: &bn::BigNumRef) -> [u8; 384] {
     le
 }
 
+fn bn_from_arr(value: &[u8; 384]) -> Result<bn::BigNum, ErrorStack> {
+    let mut be = *value;
+    be.reverse();
+    bn::BigNum::from_slice(&be)
+}
+
+fn sig_data(
+    s: &bn::BigNumRef,
+    m: &bn::BigNumRef,
+    e: &bn::BigNumRef,
+) -> Result<super::SigData, ErrorStack> {
+    // Calculate q1 and q2.
+    let mut ctx = bn::BigNumContext::new()?;
+    let mut q1 = bn::BigNum::new()?;
+    let mut qr = bn::BigNum::new()?;
+    q1.div_rem(&mut qr, &(s * s), m, &mut ctx)?;
+    let q2 = &(s * &qr) / m;
+
+    // Get the exponent.
+    let mut exponent: u32 = 0;
+    for byte in e.to_vec() {
+        exponent <<= 8;
+        exponent |= byte as u32;
+    }
+
+    Ok(super::SigData {
+        signature: arr_from_bn(s),
+        modulus: arr_from_bn(m),
+        exponent,
+        q1: arr_from_bn(&q1),
+        q2: arr_from_bn(&q2),
+    })
+}
+
 /// SHA2-256
 pub struct S256Digest(sha::Sha256);
 
@@ -36,6 +70,89 @@ impl super::Digest for S256Digest {
     }
 }
 
+/// AES-128-CMAC
+pub struct Aes128Cmac(pkey::PKey<pkey::Private>);
+
+impl super::Cmac for Aes128Cmac {
+    type Error = ErrorStack;
+
+    fn new(key: &[u8; 16]) -> Result<Self, Self::Error> {
+        let key = pkey::PKey::cmac(&symm::Cipher::aes_128_cbc(), key)?;
+        Ok(Self(key))
+    }
+
+    fn mac(&self, bytes: &[u8]) -> Result<[u8; 16], Self::Error> {
+        let mut mac = [0u8; 16];
+        let mut signer = sign::Signer::new_without_digest(&self.0)?;
+        signer.update(bytes)?;
+        signer.sign(&mut mac)?;
+        Ok(mac)
+    }
+}
+
+/// AES-128-GCM
+pub struct Aes128Gcm([u8; 16]);
+
+impl Aes128Gcm {
+    fn crypter(
+        &self,
+        mode: symm::Mode,
+        iv: &[u8; 12],
+        aad: &[u8],
+    ) -> Result<symm::Crypter, ErrorStack> {
+        let mut crypter = symm::Crypter::new(symm::Cipher::aes_128_gcm(), mode, &self.0, Some(iv))?;
+        crypter.aad_update(aad)?;
+        Ok(crypter)
+    }
+
+    // GCM is a stream mode, so the output is always the same size as the input.
+    fn update(crypter: &mut symm::Crypter, data: &mut [u8]) -> Result<(), ErrorStack> {
+        let mut buf = [0u8; 256];
+
+        for chunk in data.chunks_mut(buf.len()) {
+            let len = crypter.update(chunk, &mut buf)?;
+            chunk.copy_from_slice(&buf[..len]);
+        }
+
+        crypter.finalize(&mut buf)?;
+        Ok(())
+    }
+}
+
+impl super::Aead for Aes128Gcm {
+    type Error = ErrorStack;
+
+    fn new(key: &[u8; 16]) -> Result<Self, Self::Error> {
+        Ok(Self(*key))
+    }
+
+    fn encrypt(&self, iv: &[u8; 12], aad: &[u8], data: &mut [u8]) -> Result<[u8; 16], Self::Error> {
+        let mut tag = [0u8; 16];
+        let mut crypter = self.crypter(symm::Mode::Encrypt, iv, aad)?;
+        Self::update(&mut crypter, data)?;
+        crypter.get_tag(&mut tag)?;
+        Ok(tag)
+    }
+
+    fn decrypt(
+        &self,
+        iv: &[u8; 12],
+        aad: &[u8],
+        data: &mut [u8],
+        tag: &[u8; 16],
+    ) -> Result<(), Self::Error> {
+        let mut crypter = self.crypter(symm::Mode::Decrypt, iv, aad)?;
+        crypter.set_tag(tag)?;
+
+        // The tag is only checked by `finalize()`, after `data` was already
+        // overwritten, so do not leave the unauthenticated plaintext behind.
+        Self::update(&mut crypter, data).map_err(|e| {
+            data.fill(0);
+            e
+        })
+    }
+}
+
 /// RSA w/ SHA2-256
 pub struct RS256PrivateKey(rsa::Rsa<pkey::Private>);
 
@@ -75,34 +192,135 @@ impl super::PrivateKey for RS256PrivateKey {
         signer.update(body)?;
         signer.sign(&mut signature)?;
 
-        // Calculate q1 and q2.
         let s = bn::BigNum::from_slice(&signature)?;
-        let m = self.0.n();
-        let mut ctx = bn::BigNumContext::new()?;
-        let mut q1 = bn::BigNum::new()?;
-        let mut qr = bn::BigNum::new()?;
-        q1.div_rem(&mut qr, &(&s * &s), m, &mut ctx)?;
-        let q2 = &(&s * &qr) / m;
-
-        // Get the exponent.
-        let mut exponent: u32 = 0;
-        for byte in self.0.e().to_vec() {
-            exponent <<= 8;
-            exponent |= byte as u32;
+        sig_data(&s, self.0.n(), self.0.e())
+    }
+}
+
+/// Errors produced by `RS256PrivateKey` when decrypting
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DecryptError {
+    /// The output buffer is too short for the plaintext.
+    InvalidLength,
+    /// OpenSSL reported an error.
+    OpenSsl(ErrorStack),
+}
+
+impl From<ErrorStack> for DecryptError {
+    fn from(value: ErrorStack) -> Self {
+        Self::OpenSsl(value)
+    }
+}
+
+impl super::OaepDecrypt for RS256PrivateKey {
+    type Error = DecryptError;
+
+    fn decrypt(&self, ciphertext: &[u8], plaintext: &mut [u8]) -> Result<usize, Self::Error> {
+        let rsa_key = pkey::PKey::from_rsa(self.0.clone())?;
+        let md = openssl::hash::MessageDigest::sha256();
+        let mut decrypter = encrypt::Decrypter::new(&rsa_key)?;
+        decrypter.set_rsa_padding(rsa::Padding::PKCS1_OAEP)?;
+        decrypter.set_rsa_oaep_md(md)?;
+        decrypter.set_rsa_mgf1_md(md)?;
+
+        // OpenSSL needs room for a full modulus, which is at most 384 bytes.
+        let mut buf = [0u8; 384];
+        let len = decrypter.decrypt(ciphertext, &mut buf)?;
+        let out = plaintext
+            .get_mut(..len)
+            .ok_or(DecryptError::InvalidLength)?;
+        out.copy_from_slice(&buf[..len]);
+        buf.fill(0);
+        Ok(len)
+    }
+}
+
+/// Errors produced by `RS256PublicKey`
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PublicKeyError {
+    /// The modulus is not 3072 bits long.
+    InvalidSize,
+    /// OpenSSL reported an error.
+    OpenSsl(ErrorStack),
+}
+
+impl From<ErrorStack> for PublicKeyError {
+    fn from(value: ErrorStack) -> Self {
+        Self::OpenSsl(value)
+    }
+}
+
+/// RSA w/ SHA2-256 public key
+pub struct RS256PublicKey(rsa::Rsa<pkey::Public>);
+
+impl RS256PublicKey {
+    pub fn new(key: rsa::Rsa<pkey::Public>) -> Self {
+        assert!(key.n().num_bytes() <= 384);
+        Self(key)
+    }
+
+    // SGX only accepts 3072-bit keys, so reject any other size when parsing.
+    fn checked(key: rsa::Rsa<pkey::Public>) -> Result<Self, PublicKeyError> {
+        match key.n().num_bits() {
+            3072 => Ok(Self(key)),
+            _ => Err(PublicKeyError::InvalidSize),
         }
+    }
+}
 
-        Ok(super::SigData {
-            signature: arr_from_bn(&s),
-            modulus: arr_from_bn(m),
-            exponent,
-            q1: arr_from_bn(&q1),
-            q2: arr_from_bn(&q2),
-        })
+impl super::PublicKey for RS256PublicKey {
+    type Error = PublicKeyError;
+
+    fn from_parts(modulus: &[u8; 384], exponent: u32) -> Result<Self, Self::Error> {
+        let n = bn_from_arr(modulus)?;
+        let e = bn::BigNum::from_u32(exponent)?;
+        let key = rsa::Rsa::from_public_components(n, e)?;
+        Ok(Self::new(key))
+    }
+
+    fn from_pem(pem: &str) -> Result<Self, Self::Error> {
+        let key = rsa::Rsa::public_key_from_pem(pem.as_bytes())?;
+        Self::checked(key)
+    }
+
+    fn from_der(der: &[u8]) -> Result<Self, Self::Error> {
+        let key = rsa::Rsa::public_key_from_der(der)?;
+        Self::checked(key)
+    }
+
+    fn modulus(&self) -> [u8; 384] {
+        arr_from_bn(self.0.n())
+    }
+
+    fn sig_data(&self, signature: &[u8; 384]) -> Result<super::SigData, Self::Error> {
+        let s = bn_from_arr(signature)?;
+        Ok(sig_data(&s, self.0.n(), self.0.e())?)
+    }
+
+    fn verify(
+        &self,
+        author: &[u8],
+        body: &[u8],
+        signature: &[u8; 384],
+    ) -> Result<bool, Self::Error> {
+        let mut be = *signature;
+        be.reverse();
+
+        let rsa_key = pkey::PKey::from_rsa(self.0.clone())?;
+        let md = openssl::hash::MessageDigest::sha256();
+        let mut verifier = sign::Verifier::new(md, &rsa_key)?;
+        verifier.update(author)?;
+        verifier.update(body)?;
+        Ok(verifier.verify(&be)?)
     }
 }
 
 #[test]
 #[cfg(test)]
 fn selftest() {
-    super::selftest::<RS256PrivateKey, S256Digest>();
+    super::selftest::<RS256PrivateKey, RS256PublicKey, S256Digest>();
+    super::cmac_selftest::<Aes128Cmac>();
+    super::aead_selftest::<Aes128Gcm>();
 }
@@ -1,10 +1,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use aes::Aes128;
+use aes_gcm::aead::{AeadInPlace, KeyInit};
+use cmac::Mac;
 use num_integer::Integer;
 use num_traits::ToPrimitive;
 use rand::thread_rng;
+use rsa::pkcs8::{self, DecodePublicKey};
 use rsa::traits::PublicKeyParts;
-use rsa::{pkcs1::DecodeRsaPrivateKey, BigUint, Pkcs1v15Sign, RsaPrivateKey};
+use rsa::{pkcs1::DecodeRsaPrivateKey, BigUint, Oaep, Pkcs1v15Sign, RsaPrivateKey, RsaPublicKey};
 use sha2::{Digest, Sha256};
 
 fn arr_from_big(value: &BigUint) -> [u8; 384] {
@@ -14,6 +18,20 @@ fn arr_from_big(value: &BigUint) -> [u8; 384] {
     arr
 }
 
+fn sig_data(s: &BigUint, m: &BigUint, e: &BigUint) -> Result<super::SigData, rsa::errors::Error> {
+    // Calculate q1 and q2.
+    let (q1, qr) = (s * s).div_rem(m);
+    let q2 = (s * qr) / m;
+
+    Ok(super::SigData {
+        signature: arr_from_big(s),
+        modulus: arr_from_big(m),
+        exponent: e.to_u32().ok_or(rsa::errors::Error::InvalidExponent)?,
+        q1: arr_from_big(&q1),
+        q2: arr_from_big(&q2),
+    })
+}
+
 /// SHA2-256
 pub struct S256Digest(Sha256);
 
@@ -36,6 +54,55 @@ impl super::Digest for S256Digest {
     }
 }
 
+/// AES-128-CMAC
+pub struct Aes128Cmac(cmac::Cmac<Aes128>);
+
+impl super::Cmac for Aes128Cmac {
+    type Error = core::convert::Infallible;
+
+    #[inline]
+    fn new(key: &[u8; 16]) -> Result<Self, Self::Error> {
+        Ok(Self(<cmac::Cmac<Aes128> as KeyInit>::new(key.into())))
+    }
+
+    #[inline]
+    fn mac(&self, bytes: &[u8]) -> Result<[u8; 16], Self::Error> {
+        let mut mac = self.0.clone();
+        mac.update(bytes);
+        Ok(mac.finalize().into_bytes().into())
+    }
+}
+
+/// AES-128-GCM
+pub struct Aes128Gcm(aes_gcm::Aes128Gcm);
+
+impl super::Aead for Aes128Gcm {
+    type Error = aes_gcm::Error;
+
+    #[inline]
+    fn new(key: &[u8; 16]) -> Result<Self, Self::Error> {
+        Ok(Self(aes_gcm::Aes128Gcm::new(key.into())))
+    }
+
+    #[inline]
+    fn encrypt(&self, iv: &[u8; 12], aad: &[u8], data: &mut [u8]) -> Result<[u8; 16], Self::Error> {
+        let tag = self.0.encrypt_in_place_detached(iv.into(), aad, data)?;
+        Ok(tag.into())
+    }
+
+    #[inline]
+    fn decrypt(
+        &self,
+        iv: &[u8; 12],
+        aad: &[u8],
+        data: &mut [u8],
+        tag: &[u8; 16],
+    ) -> Result<(), Self::Error> {
+        self.0
+            .decrypt_in_place_detached(iv.into(), aad, data, tag.into())
+    }
+}
+
 /// RSA w/ SHA2-256
 pub struct RS256PrivateKey(RsaPrivateKey);
 
@@ -74,24 +141,97 @@ impl super::PrivateKey for RS256PrivateKey {
         let padding = Pkcs1v15Sign::new::<Sha256>();
         let sig = self.0.sign(padding, &hash)?;
 
-        // Calculate q1 and q2.
         let s = BigUint::from_bytes_be(&sig);
-        let m = self.0.n();
-        let (q1, qr) = (&s * &s).div_rem(m);
-        let q2 = (&s * qr) / m;
-
-        Ok(super::SigData {
-            signature: arr_from_big(&s),
-            modulus: arr_from_big(m),
-            exponent: self.0.e().to_u32().unwrap(),
-            q1: arr_from_big(&q1),
-            q2: arr_from_big(&q2),
-        })
+        sig_data(&s, self.0.n(), self.0.e())
+    }
+}
+
+impl super::OaepDecrypt for RS256PrivateKey {
+    type Error = rsa::errors::Error;
+
+    fn decrypt(&self, ciphertext: &[u8], plaintext: &mut [u8]) -> Result<usize, Self::Error> {
+        let buf = self.0.decrypt(Oaep::new::<Sha256>(), ciphertext)?;
+        let out = plaintext
+            .get_mut(..buf.len())
+            .ok_or(rsa::errors::Error::InvalidArguments)?;
+        out.copy_from_slice(&buf);
+        Ok(buf.len())
+    }
+}
+
+/// RSA w/ SHA2-256 public key
+pub struct RS256PublicKey(RsaPublicKey);
+
+impl RS256PublicKey {
+    pub fn new(key: RsaPublicKey) -> Self {
+        assert!(key.n().bits() <= 384 * 8);
+        Self(key)
+    }
+
+    // SGX only accepts 3072-bit keys, so reject any other size when parsing.
+    fn checked(key: RsaPublicKey) -> Result<Self, rsa::errors::Error> {
+        match key.n().bits() {
+            3072 => Ok(Self(key)),
+            n if n > 3072 => Err(rsa::errors::Error::ModulusTooLarge),
+            _ => Err(rsa::errors::Error::InvalidModulus),
+        }
+    }
+}
+
+impl super::PublicKey for RS256PublicKey {
+    type Error = rsa::errors::Error;
+
+    fn from_parts(modulus: &[u8; 384], exponent: u32) -> Result<Self, Self::Error> {
+        let n = BigUint::from_bytes_le(modulus);
+        let key = RsaPublicKey::new(n, BigUint::from(exponent))?;
+        Ok(Self::new(key))
+    }
+
+    fn from_pem(pem: &str) -> Result<Self, Self::Error> {
+        let key = RsaPublicKey::from_public_key_pem(pem).map_err(pkcs8::Error::from)?;
+        Self::checked(key)
+    }
+
+    fn from_der(der: &[u8]) -> Result<Self, Self::Error> {
+        let key = RsaPublicKey::from_public_key_der(der).map_err(pkcs8::Error::from)?;
+        Self::checked(key)
+    }
+
+    fn modulus(&self) -> [u8; 384] {
+        arr_from_big(self.0.n())
+    }
+
+    fn sig_data(&self, signature: &[u8; 384]) -> Result<super::SigData, Self::Error> {
+        let s = BigUint::from_bytes_le(signature);
+        sig_data(&s, self.0.n(), self.0.e())
+    }
+
+    fn verify(
+        &self,
+        author: &[u8],
+        body: &[u8],
+        signature: &[u8; 384],
+    ) -> Result<bool, Self::Error> {
+        use sha2::digest::Update;
+
+        let hash = Sha256::new().chain(author).chain(body).finalize();
+
+        let mut be = *signature;
+        be.reverse();
+
+        let padding = Pkcs1v15Sign::new::<Sha256>();
+        match self.0.verify(padding, &hash, &be) {
+            Ok(()) => Ok(true),
+            Err(rsa::errors::Error::Verification) => Ok(false),
+            Err(e) => Err(e),
+        }
     }
 }
 
 #[test]
 #[cfg(test)]
 fn selftest() {
-    super::selftest::<RS256PrivateKey, S256Digest>();
+    super::selftest::<RS256PrivateKey, RS256PublicKey, S256Digest>();
+    super::cmac_selftest::<Aes128Cmac>();
+    super::aead_selftest::<Aes128Gcm>();
 }
@@ -0,0 +1,240 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fuzzing entry points
+//!
+//! Each function in this module feeds arbitrary input to one of the parsers
+//! in this crate and exercises all accessors of the result. None of them may
+//! ever panic. They are meant to be called from a fuzzing harness such as
+//! `cargo fuzz`:
+//!
+//! ```ignore
+//! libfuzzer_sys::fuzz_target!(|data: &[u8]| sgx::fuzz::epid_quote(data));
+//! ```
+
+use core::hint::black_box;
+use core::mem::size_of;
+
+use crate::signature::Signature;
+use crate::ReportBody;
+
+/// Parses a `ReportBody` from the start of `data`.
+pub fn report_body(data: &[u8]) {
+    if let Some(bytes) = data.get(..size_of::<ReportBody>()) {
+        let body: &ReportBody = <&[u8; size_of::<ReportBody>()]>::try_from(bytes)
+            .unwrap()
+            .into();
+
+        black_box(body.misc_select());
+        black_box(body.attributes());
+        black_box(body.enclave_product_id());
+        black_box(body.enclave_security_version());
+        black_box(body.enclave_extended_product_id());
+        black_box(body.enclave_family_id());
+        black_box(body.config_id());
+        black_box(body.config_svn());
+        black_box(body.cet_attributes());
+    }
+}
+
+/// Parses an ECDSA quote from `data`.
+pub fn quote(data: &[u8]) {
+    if let Ok(quote) = crate::quote::QuoteRef::try_from(data) {
+        black_box(quote.version());
+        black_box(quote.att_key_type());
+        black_box(quote.tee_type());
+        black_box(quote.is_intel_qe());
+        black_box(quote.qe_svn());
+        black_box(quote.pce_svn());
+        black_box(quote.qe_vendor_id());
+        black_box(quote.user_data());
+        black_box(quote.signed_data());
+        report_body(quote.report_body().as_ref());
+
+        let sig = quote.signature();
+        black_box(sig.report_signature());
+        black_box(sig.attestation_key());
+        black_box(sig.qe_report_signature());
+        black_box(sig.qe_auth_data());
+        black_box(sig.cert_data_type());
+        black_box(sig.cert_data());
+        black_box(sig.ppid_info().ok());
+        if let Ok(chain) = sig.pck_chain() {
+            black_box(chain);
+            #[cfg(feature = "rcrypto")]
+            black_box(chain.leaf_certificate().ok());
+        }
+        report_body(sig.qe_report().as_ref());
+    }
+}
+
+/// Parses a legacy EPID quote from `data`.
+#[allow(deprecated)]
+pub fn epid_quote(data: &[u8]) {
+    if let Ok(quote) = crate::quote::EpidQuote::try_from(data) {
+        black_box(quote.version());
+        black_box(quote.sign_type());
+        black_box(quote.epid_group_id());
+        black_box(quote.qe_svn());
+        black_box(quote.pce_svn());
+        black_box(quote.xeid());
+        black_box(quote.basename());
+        black_box(quote.signature());
+        report_body(quote.report_body().as_ref());
+    }
+}
+
+/// Parses a `Signature` (`SIGSTRUCT`) from the start of `data`.
+pub fn signature(data: &[u8]) {
+    if let Some(bytes) = data.get(..size_of::<Signature>()) {
+        let sig = Signature::from(<[u8; size_of::<Signature>()]>::try_from(bytes).unwrap());
+
+        black_box(sig.author().date());
+        black_box(sig.author().swdefined());
+        black_box(sig.author().validate().ok());
+        black_box(sig.body().mrenclave());
+        black_box(sig.body().parameters());
+        black_box(sig.modulus());
+        black_box(sig.exponent());
+        black_box(sig.signature());
+    }
+}
+
+/// Parses the SGX extension from DER-encoded X.509 extensions in `data`.
+#[cfg(feature = "rcrypto")]
+pub fn pck_extension(data: &[u8]) {
+    use der::Decode;
+
+    if let Ok(extensions) = x509::ext::Extensions::from_der(data) {
+        if let Ok(ext) = crate::pck::SgxExtension::from_x509_extensions(&extensions) {
+            black_box(ext.fmspc);
+            black_box(ext.pcesvn);
+            black_box(ext.pceid);
+            black_box(ext.tcb_components);
+            black_box(ext.is_multi);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PEM: &str = include_str!("../tests/single_pck.pem");
+
+    // A small deterministic generator, so failures are reproducible.
+    fn xorshift(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    // Random inputs of increasing length.
+    fn inputs(mut f: impl FnMut(&[u8])) {
+        let mut buf = [0u8; 4096];
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+
+        for len in (0..buf.len()).step_by(61) {
+            for byte in buf.iter_mut() {
+                *byte = xorshift(&mut state) as u8;
+            }
+
+            f(&buf[..len]);
+        }
+    }
+
+    // A valid input, then truncated copies and copies with a few bytes
+    // flipped, so that mutations reach past the first length check.
+    fn mutations(seed: &[u8], mut f: impl FnMut(&[u8])) {
+        let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+        f(seed);
+
+        for _ in 0..256 {
+            let len = xorshift(&mut state) as usize % (seed.len() + 1);
+            f(&seed[..len]);
+
+            let mut copy = seed.to_vec();
+            for _ in 0..1 + xorshift(&mut state) % 4 {
+                let at = xorshift(&mut state) as usize % copy.len();
+                copy[at] ^= 1 << (xorshift(&mut state) % 8);
+            }
+            f(&copy);
+        }
+    }
+
+    // Encodes an ECDSA quote with the given certification data.
+    fn ecdsa_quote(version: u16, kind: u16, cert: &[u8]) -> std::vec::Vec<u8> {
+        let mut qe = std::vec![0x11u8; 384 + 64];
+        qe.extend_from_slice(&2u16.to_le_bytes());
+        qe.extend_from_slice(&[1, 2]);
+        qe.extend_from_slice(&kind.to_le_bytes());
+        qe.extend_from_slice(&(cert.len() as u32).to_le_bytes());
+        qe.extend_from_slice(cert);
+
+        let mut sig = std::vec![0x22u8; 128];
+        if version == 4 {
+            sig.extend_from_slice(&6u16.to_le_bytes());
+            sig.extend_from_slice(&(qe.len() as u32).to_le_bytes());
+        }
+        sig.extend_from_slice(&qe);
+
+        let mut bytes = std::vec![0u8; 48 + 384];
+        bytes[..2].copy_from_slice(&version.to_le_bytes());
+        bytes[2] = 2;
+        bytes[12..28].copy_from_slice(&crate::quote::QuoteRef::INTEL_QE_VENDOR_ID);
+        bytes.extend_from_slice(&(sig.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&sig);
+        bytes
+    }
+
+    #[test]
+    fn smoke() {
+        inputs(report_body);
+        inputs(quote);
+        inputs(epid_quote);
+        inputs(signature);
+
+        #[cfg(feature = "rcrypto")]
+        inputs(pck_extension);
+    }
+
+    #[test]
+    fn seeded() {
+        use crate::quote::QuoteRef;
+
+        let chain = std::format!("{PEM}{PEM}{PEM}\0");
+        let ppid = [0x33u8; 384 + 20];
+        for seed in [
+            ecdsa_quote(3, 5, chain.as_bytes()),
+            ecdsa_quote(4, 5, chain.as_bytes()),
+            ecdsa_quote(3, 3, &ppid),
+        ] {
+            let parsed = QuoteRef::try_from(&seed[..]).unwrap();
+            let sig = parsed.signature();
+            assert!(sig.pck_chain().is_ok() || sig.ppid_info().is_ok());
+
+            mutations(&seed, quote);
+        }
+
+        let mut epid = std::vec![0u8; 48 + 384];
+        epid[0] = 2;
+        epid.extend_from_slice(&[3, 0, 0, 0, 1, 2, 3]);
+        #[allow(deprecated)]
+        crate::quote::EpidQuote::try_from(&epid[..]).unwrap();
+        mutations(&epid, epid_quote);
+
+        let sig = include_bytes!("../tests/encl.ss");
+        assert!(Signature::from(*sig).author().validate().is_ok());
+        mutations(sig, signature);
+
+        #[cfg(feature = "rcrypto")]
+        {
+            use der::{Decode, Encode};
+
+            let pck = include_bytes!("../tests/single_pck.crt");
+            let pck = x509::Certificate::from_der(pck).unwrap();
+            let ext = pck.tbs_certificate.extensions.unwrap().to_der().unwrap();
+            mutations(&ext, pck_extension);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use core::fmt::{Debug, Formatter, Result};
+
+/// Formats bytes as hex in `Debug` output
+///
+/// Values of up to 32 bytes (such as measurements) are shown in full. Longer
+/// values only show the first and last 8 bytes along with their length.
+pub(crate) struct Hex<'a>(pub &'a [u8]);
+
+impl Debug for Hex<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let write = |f: &mut Formatter<'_>, bytes: &[u8]| {
+            bytes.iter().try_for_each(|b| write!(f, "{b:02x}"))
+        };
+
+        if self.0.len() <= 32 {
+            return write(f, self.0);
+        }
+
+        write(f, &self.0[..8])?;
+        f.write_str("..")?;
+        write(f, &self.0[self.0.len() - 8..])?;
+        write!(f, " ({} bytes)", self.0.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Hex;
+
+    #[test]
+    fn debug() {
+        assert_eq!(format!("{:?}", Hex(&[])), "");
+        assert_eq!(format!("{:?}", Hex(&[0x0a, 0xff])), "0aff");
+        assert_eq!(format!("{:?}", Hex(&[0xab; 32])), "ab".repeat(32));
+
+        let mut bytes = [0u8; 384];
+        bytes[0] = 1;
+        bytes[383] = 2;
+        assert_eq!(
+            format!("{:?}", Hex(&bytes)),
+            "0100000000000000..0000000000000002 (384 bytes)"
+        );
+    }
+}
@@ -0,0 +1,281 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Key derivation
+//!
+//! The types in this module are typically used by an enclave to request
+//! keys from the CPU using `ENCLU[EGETKEY]`.
+
+use crate::hex::Hex;
+use crate::parameters::{Attributes, Features, MiscSelect, Xfrm};
+
+/// The name of the key to derive
+#[repr(u16)]
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum KeyName {
+    /// Used by the launch enclave to MAC an `EinitToken` (launch key)
+    EinitToken = 0,
+    /// Used by the provisioning enclave (requires `Features::PROVISIONING_KEY`)
+    Provision = 1,
+    /// Used by the provisioning enclave to seal data (requires `Features::PROVISIONING_KEY`)
+    ProvisionSeal = 2,
+    /// Used to verify the MAC of a `Report` targeted at this enclave
+    Report = 3,
+    /// Used to seal data
+    Seal = 4,
+}
+
+bitflags::bitflags! {
+    /// The identity values a key is bound to
+    #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+    pub struct KeyPolicy: u16 {
+        /// Derive the key from the enclave measurement
+        const MRENCLAVE = 1 << 0;
+
+        /// Derive the key from the enclave signer
+        const MRSIGNER = 1 << 1;
+
+        /// Do not derive the key from the ISV Product ID
+        const NOISVPRODID = 1 << 2;
+
+        /// Derive the key from the `configid` and `configsvn` (requires KSS)
+        const CONFIGID = 1 << 3;
+
+        /// Derive the key from the ISV Family ID (requires KSS)
+        const ISVFAMILYID = 1 << 4;
+
+        /// Derive the key from the ISV extended Product ID (requires KSS)
+        const ISVEXTPRODID = 1 << 5;
+    }
+}
+
+/// Error codes for `KeyRequest::get_key()`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GetKeyError {
+    /// The attributes of the enclave do not permit this key.
+    InvalidAttribute,
+    /// The requested CPUSVN is beyond the current CPUSVN.
+    InvalidCpuSvn,
+    /// The requested ISVSVN is beyond the current ISVSVN.
+    InvalidIsvSvn,
+    /// The key name is not supported.
+    InvalidKeyName,
+    /// `EGETKEY` returned an undocumented error code.
+    Unknown(u64),
+}
+
+/// Error codes for `KeyRequest::try_from()`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum KeyRequestError {
+    /// The key name is unknown.
+    InvalidKeyName,
+    /// The policy contains unknown bits.
+    InvalidPolicy,
+    /// The reserved fields are not zero.
+    InvalidReserved,
+}
+
+/// A key request
+///
+/// This structure selects the key derived by `ENCLU[EGETKEY]` and which
+/// values it is bound to.
+///
+/// For more information see:
+///
+/// [Intel® 64 and IA-32 Architectures Software Developer's Manual Volume 3 (3A, 3B, 3C & 3D): System Programming Guide](https://www.intel.com/content/www/us/en/architecture-and-technology/64-ia-32-architectures-software-developer-vol-3d-part-4-manual.html)
+///
+/// Table 38-24. Layout of KEYREQUEST Data Structure
+#[derive(Copy, Clone)]
+#[repr(C, align(512))]
+pub struct KeyRequest {
+    pub name: KeyName,
+    pub policy: KeyPolicy,
+    pub isvsvn: u16,
+    reserved0: u16,
+    pub cpusvn: [u8; 16],
+    pub attribute_mask: Attributes,
+    pub keyid: [u8; 32],
+    pub misc_mask: MiscSelect,
+    pub configsvn: u16,
+    reserved1: [u8; 434],
+}
+
+impl core::fmt::Debug for KeyRequest {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("KeyRequest")
+            .field("name", &self.name)
+            .field("policy", &self.policy)
+            .field("isvsvn", &self.isvsvn)
+            .field("cpusvn", &Hex(&self.cpusvn))
+            .field("attribute_mask", &self.attribute_mask)
+            .field("keyid", &Hex(&self.keyid))
+            .field("misc_mask", &self.misc_mask)
+            .field("configsvn", &self.configsvn)
+            .finish()
+    }
+}
+
+// SAFETY: This is safe because `KeyRequest` has no padding.
+impl From<KeyRequest> for [u8; 512] {
+    fn from(value: KeyRequest) -> Self {
+        unsafe { core::mem::transmute(value) }
+    }
+}
+
+impl TryFrom<&[u8; 512]> for KeyRequest {
+    type Error = KeyRequestError;
+
+    fn try_from(value: &[u8; 512]) -> Result<Self, Self::Error> {
+        let le16 = |i: usize| u16::from_le_bytes([value[i], value[i + 1]]);
+        let le32 = |i: usize| u32::from_le_bytes(value[i..i + 4].try_into().unwrap());
+        let le64 = |i: usize| u64::from_le_bytes(value[i..i + 8].try_into().unwrap());
+
+        let name = match le16(0) {
+            0 => KeyName::EinitToken,
+            1 => KeyName::Provision,
+            2 => KeyName::ProvisionSeal,
+            3 => KeyName::Report,
+            4 => KeyName::Seal,
+            _ => return Err(KeyRequestError::InvalidKeyName),
+        };
+
+        let policy = KeyPolicy::from_bits(le16(2)).ok_or(KeyRequestError::InvalidPolicy)?;
+
+        if le16(6) != 0 || value[78..].iter().any(|b| *b != 0) {
+            return Err(KeyRequestError::InvalidReserved);
+        }
+
+        let mut request = Self::new(name, policy);
+        request.isvsvn = le16(4);
+        request.cpusvn.copy_from_slice(&value[8..24]);
+        request.attribute_mask = Attributes::new(
+            Features::from_bits_retain(le64(24)),
+            Xfrm::from_bits_retain(le64(32)),
+        );
+        request.keyid.copy_from_slice(&value[40..72]);
+        request.misc_mask = MiscSelect::from_bits_retain(le32(72));
+        request.configsvn = le16(76);
+        Ok(request)
+    }
+}
+
+impl KeyRequest {
+    /// Creates a new request for the named key.
+    ///
+    /// All other fields are zero and may be adjusted before deriving the key.
+    pub const fn new(name: KeyName, policy: KeyPolicy) -> Self {
+        Self {
+            name,
+            policy,
+            isvsvn: 0,
+            reserved0: 0,
+            cpusvn: [0; 16],
+            attribute_mask: Attributes::new(Features::empty(), Xfrm::empty()),
+            keyid: [0; 32],
+            misc_mask: MiscSelect::empty(),
+            configsvn: 0,
+            reserved1: [0; 434],
+        }
+    }
+
+    /// Derives the requested key using `ENCLU[EGETKEY]`.
+    ///
+    /// This MUST be called from inside an enclave.
+    #[inline]
+    #[cfg(target_arch = "x86_64")]
+    pub fn get_key(&self) -> Result<[u8; 16], GetKeyError> {
+        // EGETKEY requires the output to be 16-byte aligned.
+        #[repr(C, align(16))]
+        struct Key([u8; 16]);
+
+        let mut key = Key([0; 16]);
+        let ret: u64;
+
+        unsafe {
+            core::arch::asm!(
+                "xchg       {RBX}, rbx",
+                "enclu",
+                "mov        rbx, {RBX}",
+
+                RBX = inout(reg) self => _,
+                in("rax") crate::enclu::EGETKEY,
+                in("rcx") &mut key,
+                lateout("rax") ret,
+            );
+        }
+
+        match ret {
+            0 => Ok(key.0),
+            2 => Err(GetKeyError::InvalidAttribute),
+            32 => Err(GetKeyError::InvalidCpuSvn),
+            64 => Err(GetKeyError::InvalidIsvSvn),
+            256 => Err(GetKeyError::InvalidKeyName),
+            ret => Err(GetKeyError::Unknown(ret)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{KeyName, KeyPolicy, KeyRequest, KeyRequestError};
+    use crate::parameters::{Attributes, Features, MiscSelect, Xfrm};
+    use testaso::testaso;
+
+    testaso! {
+        struct KeyRequest: 512, 512 => {
+            name: 0,
+            policy: 2,
+            isvsvn: 4,
+            reserved0: 6,
+            cpusvn: 8,
+            attribute_mask: 24,
+            keyid: 40,
+            misc_mask: 72,
+            configsvn: 76,
+            reserved1: 78
+        }
+    }
+
+    #[test]
+    fn values() {
+        let request = KeyRequest::new(KeyName::ProvisionSeal, KeyPolicy::ISVEXTPRODID);
+        assert_eq!(request.name as u16, 2);
+        assert_eq!(request.policy.bits(), 0x20);
+        assert_eq!(KeyName::Provision as u16, 1);
+        assert_eq!(KeyPolicy::all().bits(), 0x3f);
+    }
+
+    #[test]
+    fn bytes() {
+        let mut request = KeyRequest::new(KeyName::Seal, KeyPolicy::MRSIGNER);
+        request.isvsvn = 3;
+        request.cpusvn = [7; 16];
+        request.attribute_mask =
+            Attributes::new(Features::from_bits_retain(0xff00_0000_0000_000b), Xfrm::X87);
+        request.keyid = [9; 32];
+        request.misc_mask = MiscSelect::from_bits_retain(0xf000_0000);
+        request.configsvn = 5;
+
+        let bytes = <[u8; 512]>::from(request);
+        assert_eq!(bytes[..6], [4, 0, 2, 0, 3, 0]);
+        assert_eq!(bytes[76], 5);
+
+        let parsed = KeyRequest::try_from(&bytes).unwrap();
+        assert_eq!(<[u8; 512]>::from(parsed), bytes);
+        assert_eq!(parsed.attribute_mask, request.attribute_mask);
+        assert_eq!(parsed.misc_mask, request.misc_mask);
+
+        for (offset, value, err) in [
+            (0, 5, KeyRequestError::InvalidKeyName),
+            (2, 0x40, KeyRequestError::InvalidPolicy),
+            (6, 1, KeyRequestError::InvalidReserved),
+            (511, 1, KeyRequestError::InvalidReserved),
+        ] {
+            let mut bad = bytes;
+            bad[offset] = value;
+            assert_eq!(KeyRequest::try_from(&bad).unwrap_err(), err);
+        }
+    }
+}
@@ -8,14 +8,18 @@
 //! How to use this crate partly depends on what you are trying to accomplish:
 //!
 //!   1. If you are an enclave developer, you probably want the `parameters`
-//!      and `ssa` modules.
+//!      and `ssa` modules. You may also want the `key` and `sealing` modules
+//!      to derive keys.
 //!   2. If you are signing an enclave, you probably want the `signature` and
 //!      `crypto` modules.
 //!   3. If you are developing an enclave loader, you probably want the
 //!      `parameters` and `page` modules. However, you may also want the
-//!      `signature` module to load a signature.
+//!      `signature` module to load a signature and the `uapi` module to
+//!      talk to the Linux kernel.
 //!   4. If you want to parse fields from the CPU certificate, you probably
 //!      want the `pck` module and `rcrypto` feature.
+//!   5. If you are inspecting attestation evidence, you probably want the
+//!      `quote` module.
 
 #![no_std]
 #![deny(clippy::exhaustive_enums)]
@@ -26,13 +30,20 @@
 extern crate std;
 
 pub mod crypto;
+pub mod key;
 pub mod page;
 pub mod parameters;
+pub mod quote;
+pub mod sealing;
 pub mod signature;
+pub mod uapi;
 
 #[cfg(feature = "rcrypto")]
 pub mod pck;
 
+#[cfg(feature = "fuzzing")]
+pub mod fuzz;
+
 #[cfg(target_arch = "x86_64")]
 pub mod ssa;
 
@@ -48,6 +59,11 @@ pub mod enclu {
     pub const EACCEPTCOPY: usize = 0x07;
 }
 
+mod hex;
+mod measurement;
 mod report;
 
-pub use report::{Report, ReportBody};
+pub use measurement::{Measurement, MrSigner, ParseMeasurementError};
+pub use report::{
+    Report, Report2, ReportBody, ReportData, ReportMac, ReportVerifyError, TargetInfo,
+};
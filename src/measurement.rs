@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hexadecimal measurement values
+
+use core::fmt::{Debug, Display, Formatter, Result};
+use core::str::FromStr;
+
+/// The input is not a 64 character hexadecimal string
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ParseMeasurementError(());
+
+macro_rules! measurement {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        ///
+        /// The value is displayed and parsed as 64 lowercase hexadecimal
+        /// characters. Comparisons take constant time.
+        #[derive(Copy, Clone, Default, Eq)]
+        pub struct $name([u8; 32]);
+
+        impl From<[u8; 32]> for $name {
+            fn from(value: [u8; 32]) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for [u8; 32] {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl AsRef<[u8; 32]> for $name {
+            fn as_ref(&self) -> &[u8; 32] {
+                &self.0
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.0.iter().zip(&other.0).fold(0, |acc, (l, r)| acc | (l ^ r)) == 0
+            }
+        }
+
+        impl PartialEq<[u8; 32]> for $name {
+            fn eq(&self, other: &[u8; 32]) -> bool {
+                *self == Self(*other)
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+                for byte in self.0 {
+                    write!(f, "{byte:02x}")?;
+                }
+
+                Ok(())
+            }
+        }
+
+        impl Debug for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+                write!(f, "{}({self})", stringify!($name))
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseMeasurementError;
+
+            fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
+                parse(s).map(Self)
+            }
+        }
+    };
+}
+
+fn parse(s: &str) -> core::result::Result<[u8; 32], ParseMeasurementError> {
+    let digit = |c: u8| match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(ParseMeasurementError(())),
+    };
+
+    if s.len() != 64 {
+        return Err(ParseMeasurementError(()));
+    }
+
+    let mut value = [0u8; 32];
+    for (byte, pair) in value.iter_mut().zip(s.as_bytes().chunks(2)) {
+        *byte = digit(pair[0])? << 4 | digit(pair[1])?;
+    }
+
+    Ok(value)
+}
+
+measurement! {
+    /// An enclave measurement (`MRENCLAVE`)
+    ///
+    /// This is produced by the `Hasher` and reported in
+    /// `ReportBody::mrenclave`.
+    Measurement
+}
+
+measurement! {
+    /// A signer measurement (`MRSIGNER`)
+    ///
+    /// This is produced by `Signature::mrsigner()` with SHA2-256 and reported
+    /// in `ReportBody::mrsigner`.
+    MrSigner
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Measurement, MrSigner, ParseMeasurementError};
+    use std::string::ToString;
+
+    const HEX: &str = "2f9f8fd4fe12d77232f1d87571ca8252ca27714efe7705e46222cffd5a22e8c4";
+
+    #[test]
+    fn hex() {
+        let signer: MrSigner = HEX.parse().unwrap();
+        assert_eq!(signer.as_ref()[..2], [0x2f, 0x9f]);
+        assert_eq!(signer.to_string(), HEX);
+        assert_eq!(std::format!("{signer:?}"), std::format!("MrSigner({HEX})"));
+        assert_eq!(HEX.to_uppercase().parse::<MrSigner>(), Ok(signer));
+
+        let measurement = Measurement::from([0xab; 32]);
+        assert_eq!(measurement, [0xab; 32]);
+        assert_ne!(measurement, Measurement::default());
+        assert_eq!(measurement.to_string().parse(), Ok(measurement));
+
+        let err = Err(ParseMeasurementError(()));
+        assert_eq!(HEX[1..].parse::<Measurement>(), err);
+        assert_eq!(HEX.replace('2', "g").parse::<Measurement>(), err);
+        assert_eq!("é".repeat(32).parse::<Measurement>(), err);
+    }
+}
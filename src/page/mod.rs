@@ -5,14 +5,18 @@
 //! The most important structures in thie module are:
 //!   1. `Secs`: controls enclave features during creation
 //!   2. `SecInfo`: controls access permissions for enclave pages
+//!   3. `Tcs`: controls where threads enter the enclave
 
 mod class;
 mod flags;
 mod secs;
 mod sinfo;
+mod tcs;
 
 pub use class::Class;
 pub use flags::Flags;
-pub use secs::Secs;
+pub use secs::{Secs, SecsError};
 pub use sinfo::AcceptError;
 pub use sinfo::SecInfo;
+pub use sinfo::SecInfoError;
+pub use tcs::{Tcs, TcsError, TcsFlags};
@@ -1,8 +1,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::parameters::{Attributes, MiscSelect, Parameters};
+use crate::hex::Hex;
+use crate::parameters::{Attributes, CetAttributes, MiscSelect, Parameters};
 use core::num::NonZeroU32;
 
+/// Error codes for `Parameters::try_secs()`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SecsError {
+    /// The size is not a power of two of at least two pages.
+    InvalidSize,
+    /// The size exceeds the maximum enclave size of the platform.
+    TooLarge,
+    /// The base address is not naturally aligned to the size.
+    UnalignedBase,
+}
+
 impl Parameters {
     /// Creates a new `Secs` instance
     ///
@@ -16,20 +29,57 @@ impl Parameters {
     ///
     /// The intersection of these two sets forms the set of features enabled.
     pub fn secs(&self, base: *const (), size: usize, ssa_frame_pages: NonZeroU32) -> Secs {
+        self.secs_unchecked(base as u64, size as u64, ssa_frame_pages)
+    }
+
+    /// Creates a new `Secs` instance after checking the `ECREATE` rules
+    ///
+    /// This is like `Parameters::secs()` but takes the `base` address as a
+    /// number, so it can be used without mapping any memory. The `size` must
+    /// be a power of two of at least two pages and `base` must be aligned to
+    /// it. The `max_size` is the platform limit, i.e. `1 << MaxEnclaveSize`
+    /// as reported by CPUID leaf 12H for the enclave mode.
+    pub fn try_secs(
+        &self,
+        base: u64,
+        size: u64,
+        ssa_frame_pages: NonZeroU32,
+        max_size: u64,
+    ) -> Result<Secs, SecsError> {
+        if size < 2 * 4096 || !size.is_power_of_two() {
+            return Err(SecsError::InvalidSize);
+        }
+
+        if size > max_size {
+            return Err(SecsError::TooLarge);
+        }
+
+        if base & (size - 1) != 0 {
+            return Err(SecsError::UnalignedBase);
+        }
+
+        Ok(self.secs_unchecked(base, size, ssa_frame_pages))
+    }
+
+    fn secs_unchecked(&self, base: u64, size: u64, ssa_frame_pages: NonZeroU32) -> Secs {
         Secs {
-            size: size as u64,
-            baseaddr: base as u64,
+            size,
+            baseaddr: base,
             ssaframesize: ssa_frame_pages,
             miscselect: self.misc.data & self.misc.mask,
-            reserved0: [0; 24],
+            cet_leg_bitmap_offset: self.cet_legacy_bitmap,
+            cet_attributes: self.cet.data & self.cet.mask,
+            reserved0: [0; 15],
             attributes: self.attr.data & self.attr.mask,
             mrenclave: [0; 32],
             reserved1: [0; 32],
             mrsigner: [0; 32],
-            reserved2: [0; 12],
+            reserved2: [0; 32],
+            configid: self.configid,
             pid: self.pid,
             svn: self.svn,
-            reserved3: [0; 7],
+            configsvn: self.configsvn,
+            reserved3: [0; 26],
             reserved4: [[0; 28]; 17],
         }
     }
@@ -46,15 +96,19 @@ pub struct Secs {
     baseaddr: u64,
     ssaframesize: NonZeroU32,
     miscselect: MiscSelect,
-    reserved0: [u8; 24],
+    cet_leg_bitmap_offset: u64,
+    cet_attributes: CetAttributes,
+    reserved0: [u8; 15],
     attributes: Attributes,
     mrenclave: [u8; 32],
     reserved1: [u8; 32],
     mrsigner: [u8; 32],
-    reserved2: [u64; 12],
+    reserved2: [u8; 32],
+    configid: [u8; 64],
     pid: u16,
     svn: u16,
-    reserved3: [u32; 7],
+    configsvn: u16,
+    reserved3: [u8; 26],
     reserved4: [[u64; 28]; 17],
 }
 
@@ -66,15 +120,21 @@ impl core::fmt::Debug for Secs {
             .field("ssaframesize", &self.ssaframesize)
             .field("miscselect", &self.miscselect)
             .field("attributes", &self.attributes)
+            .field("cet_leg_bitmap_offset", &self.cet_leg_bitmap_offset)
+            .field("cet_attributes", &self.cet_attributes)
             .field("pid", &self.pid)
             .field("svn", &self.svn)
+            .field("configid", &Hex(&self.configid))
+            .field("configsvn", &self.configsvn)
             .finish()
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::Secs;
+    use super::{Secs, SecsError};
+    use crate::parameters::Parameters;
+    use core::num::NonZeroU32;
     use testaso::testaso;
 
     testaso! {
@@ -83,16 +143,43 @@ mod test {
             baseaddr: 8,
             ssaframesize: 16,
             miscselect: 20,
-            reserved0: 24,
+            cet_leg_bitmap_offset: 24,
+            cet_attributes: 32,
+            reserved0: 33,
             attributes: 48,
             mrenclave: 64,
             reserved1: 96,
             mrsigner: 128,
             reserved2: 160,
+            configid: 192,
             pid: 256,
             svn: 258,
-            reserved3: 260,
+            configsvn: 260,
+            reserved3: 262,
             reserved4: 288
         }
     }
+
+    #[test]
+    fn try_secs() {
+        const MAX: u64 = 1 << 36;
+
+        let ssa = NonZeroU32::new(1).unwrap();
+        let p = Parameters::default();
+
+        let secs = p.try_secs(1 << 32, 1 << 20, ssa, MAX).unwrap();
+        assert_eq!(secs.baseaddr, 1 << 32);
+        assert_eq!(secs.size, 1 << 20);
+
+        for size in [0, 4096, 3 << 20] {
+            let err = p.try_secs(0, size, ssa, MAX).unwrap_err();
+            assert_eq!(err, SecsError::InvalidSize);
+        }
+
+        let err = p.try_secs(0, MAX << 1, ssa, MAX).unwrap_err();
+        assert_eq!(err, SecsError::TooLarge);
+
+        let err = p.try_secs(1 << 19, 1 << 20, ssa, MAX).unwrap_err();
+        assert_eq!(err, SecsError::UnalignedBase);
+    }
 }
@@ -60,7 +60,61 @@ pub enum AcceptError {
     PageAttributesMismatch,
 }
 
+/// Error codes for `SecInfo::try_new()`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SecInfoError {
+    /// Pages of this class are never loaded into an enclave.
+    InvalidClass,
+    /// The flags are not permitted for pages of this class.
+    InvalidFlags,
+}
+
 impl SecInfo {
+    const fn raw(class: Class, flags: Flags) -> Self {
+        Self {
+            class,
+            flags,
+            reserved: [0; 31],
+        }
+    }
+
+    /// A regular page which is readable and executable
+    pub const fn reg_rx() -> Self {
+        Self::raw(Class::Regular, Flags::READ.union(Flags::EXECUTE))
+    }
+
+    /// A regular page which is readable and writable
+    pub const fn reg_rw() -> Self {
+        Self::raw(Class::Regular, Flags::READ.union(Flags::WRITE))
+    }
+
+    /// Create a new instance, validating the flags against the class.
+    ///
+    /// This catches combinations that the CPU rejects when adding a page:
+    ///   * `Secs` pages are created by ECREATE and are never added.
+    ///   * Regular pages may only carry permissions and `WRITE` requires
+    ///     `READ`.
+    ///   * Shadow stack pages must be exactly readable and writable.
+    ///   * All other pages must have empty flags.
+    pub fn try_new(class: Class, flags: Flags) -> Result<SecInfo, SecInfoError> {
+        let valid = match class {
+            Class::Secs => return Err(SecInfoError::InvalidClass),
+            Class::Regular => {
+                let perms = Flags::READ | Flags::WRITE | Flags::EXECUTE;
+                perms.contains(flags)
+                    && (flags.contains(Flags::READ) || !flags.contains(Flags::WRITE))
+            }
+            Class::ShadowStackFirst | Class::ShadowStackRest => flags == Flags::READ | Flags::WRITE,
+            _ => flags.is_empty(),
+        };
+
+        match valid {
+            true => Ok(Self::raw(class, flags)),
+            false => Err(SecInfoError::InvalidFlags),
+        }
+    }
+
     /// Create a new instance.
     #[inline]
     pub fn new(class: Class, flags: impl Into<Option<Flags>>) -> SecInfo {
@@ -70,11 +124,40 @@ impl SecInfo {
             _ => Flags::empty(),
         });
 
-        Self {
-            class,
-            flags,
-            reserved: [0; 31],
-        }
+        Self::raw(class, flags)
+    }
+
+    /// The `SecInfo` to accept a page added by `ENCLS[EAUG]`
+    ///
+    /// Pass this to `accept()` (or `accept_copy()`) for each page the host
+    /// added to the running enclave.
+    pub const fn augmented() -> Self {
+        Self::raw(
+            Class::Regular,
+            Flags::READ.union(Flags::WRITE).union(Flags::PENDING),
+        )
+    }
+
+    /// The `SecInfo` to accept a page restricted by `ENCLS[EMODPR]`
+    ///
+    /// Changing the permissions of a regular page takes these steps:
+    ///   1. To remove permissions, the host issues the
+    ///      `uapi::ENCLAVE_RESTRICT_PERMISSIONS` ioctl. The enclave then calls
+    ///      `accept()` with this `SecInfo`, where `perms` are the new
+    ///      permissions.
+    ///   2. To add permissions, the enclave calls `extend()` with the added
+    ///      permissions. The host must then update the page table mapping.
+    pub const fn restricted(perms: Flags) -> Self {
+        let perms = perms.intersection(Flags::READ.union(Flags::WRITE).union(Flags::EXECUTE));
+        Self::raw(Class::Regular, perms.union(Flags::RESTRICTED))
+    }
+
+    /// The `SecInfo` to accept a page whose type was changed by `ENCLS[EMODT]`
+    ///
+    /// The host issues the `uapi::ENCLAVE_MODIFY_TYPES` ioctl and the enclave
+    /// then calls `accept()` with this `SecInfo`, where `class` is the new type.
+    pub const fn modified(class: Class) -> Self {
+        Self::raw(class, Flags::MODIFIED)
     }
 
     /// Get the flags
@@ -171,6 +254,56 @@ mod test {
         }
     }
 
+    #[test]
+    fn try_new() {
+        let rw = Flags::READ | Flags::WRITE;
+
+        assert_eq!(
+            SecInfo::try_new(Class::Secs, Flags::empty()).unwrap_err(),
+            SecInfoError::InvalidClass
+        );
+        assert!(SecInfo::try_new(Class::Tcs, Flags::empty()).is_ok());
+        assert_eq!(
+            SecInfo::try_new(Class::Tcs, Flags::READ).unwrap_err(),
+            SecInfoError::InvalidFlags
+        );
+        assert!(SecInfo::try_new(Class::Regular, Flags::empty()).is_ok());
+        assert!(SecInfo::try_new(Class::Regular, rw | Flags::EXECUTE).is_ok());
+        assert_eq!(
+            SecInfo::try_new(Class::Regular, Flags::WRITE).unwrap_err(),
+            SecInfoError::InvalidFlags
+        );
+        assert_eq!(
+            SecInfo::try_new(Class::Regular, Flags::READ | Flags::PENDING).unwrap_err(),
+            SecInfoError::InvalidFlags
+        );
+        assert!(SecInfo::try_new(Class::ShadowStackFirst, rw).is_ok());
+        assert_eq!(
+            SecInfo::try_new(Class::ShadowStackRest, Flags::READ).unwrap_err(),
+            SecInfoError::InvalidFlags
+        );
+
+        assert_eq!(format!("{}", SecInfo::reg_rx()), "RX");
+        assert_eq!(format!("{}", SecInfo::reg_rw()), "RW");
+    }
+
+    #[test]
+    fn accept() {
+        let rw = Flags::READ | Flags::WRITE;
+
+        let sinfo = SecInfo::augmented();
+        assert_eq!(sinfo.class(), Class::Regular);
+        assert_eq!(sinfo.flags(), rw | Flags::PENDING);
+
+        let sinfo = SecInfo::restricted(Flags::READ | Flags::PENDING);
+        assert_eq!(sinfo.class(), Class::Regular);
+        assert_eq!(sinfo.flags(), Flags::READ | Flags::RESTRICTED);
+
+        let sinfo = SecInfo::modified(Class::Trimmed);
+        assert_eq!(sinfo.class(), Class::Trimmed);
+        assert_eq!(sinfo.flags(), Flags::MODIFIED);
+    }
+
     #[test]
     fn display() {
         assert_eq!(format!("{}", SecInfo::from(Class::Tcs)), "T");
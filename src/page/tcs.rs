@@ -0,0 +1,261 @@
+// SPDX-License-Identifier: Apache-2.0
+
+const PAGE: u64 = 4096;
+
+bitflags::bitflags! {
+    /// The flags of a thread
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub struct TcsFlags: u64 {
+        /// Allows debugging of the thread in a debug enclave
+        const DBGOPTIN = 1 << 0;
+
+        /// Enables AEX notifications for the thread
+        const AEXNOTIFY = 1 << 1;
+    }
+}
+
+/// Error codes for `Tcs::validate()`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TcsError {
+    /// The flags contain unknown bits.
+    InvalidFlags,
+    /// The entry point is outside of the enclave.
+    InvalidEntry,
+    /// The SSA frames are not page-aligned or are outside of the enclave.
+    InvalidSsa,
+    /// The FS base is not page-aligned or is outside of the enclave.
+    InvalidFsBase,
+    /// The GS base is not page-aligned or is outside of the enclave.
+    InvalidGsBase,
+}
+
+/// Thread Control Structure (TCS) page
+///
+/// Each `Tcs` page added to an enclave allows one thread to enter it. All
+/// offsets are relative to the enclave base address.
+///
+/// For more information see:
+///
+/// [Intel® 64 and IA-32 Architectures Software Developer's Manual Volume 3 (3A, 3B, 3C & 3D): System Programming Guide](https://www.intel.com/content/www/us/en/architecture-and-technology/64-ia-32-architectures-software-developer-vol-3d-part-4-manual.html)
+///
+/// Table 35-9. Layout of Thread Control Structure (TCS)
+#[derive(Copy, Clone)]
+#[repr(C, align(4096))]
+pub struct Tcs {
+    reserved0: u64,
+    flags: TcsFlags,
+    ossa: u64,
+    cssa: u32,
+    nssa: u32,
+    oentry: u64,
+    reserved1: u64,
+    ofsbase: u64,
+    ogsbase: u64,
+    fslimit: u32,
+    gslimit: u32,
+    reserved2: [u64; 503],
+}
+
+impl core::fmt::Debug for Tcs {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Tcs")
+            .field("flags", &self.flags)
+            .field("ossa", &self.ossa)
+            .field("cssa", &self.cssa)
+            .field("nssa", &self.nssa)
+            .field("oentry", &self.oentry)
+            .field("ofsbase", &self.ofsbase)
+            .field("ogsbase", &self.ogsbase)
+            .field("fslimit", &self.fslimit)
+            .field("gslimit", &self.gslimit)
+            .finish()
+    }
+}
+
+// SAFETY: This is safe because `Tcs` has no padding.
+impl AsRef<[u8; 4096]> for Tcs {
+    fn as_ref(&self) -> &[u8; 4096] {
+        unsafe { &*(self as *const Self as *const [u8; 4096]) }
+    }
+}
+
+impl Tcs {
+    /// Creates a new thread
+    ///
+    /// The thread enters the enclave at `oentry` and uses the `nssa` SSA
+    /// frames starting at `ossa`. The FS and GS bases default to zero.
+    pub const fn new(oentry: u64, ossa: u64, nssa: u32) -> Self {
+        Self {
+            reserved0: 0,
+            flags: TcsFlags::empty(),
+            ossa,
+            cssa: 0,
+            nssa,
+            oentry,
+            reserved1: 0,
+            ofsbase: 0,
+            ogsbase: 0,
+            fslimit: u32::MAX,
+            gslimit: u32::MAX,
+            reserved2: [0; 503],
+        }
+    }
+
+    /// Sets the flags of the thread
+    pub const fn with_flags(mut self, flags: TcsFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Sets the FS base offset and limit
+    ///
+    /// The limit is only used by 32-bit enclaves.
+    pub const fn with_fs(mut self, ofsbase: u64, fslimit: u32) -> Self {
+        self.ofsbase = ofsbase;
+        self.fslimit = fslimit;
+        self
+    }
+
+    /// Sets the GS base offset and limit
+    ///
+    /// The limit is only used by 32-bit enclaves.
+    pub const fn with_gs(mut self, ogsbase: u64, gslimit: u32) -> Self {
+        self.ogsbase = ogsbase;
+        self.gslimit = gslimit;
+        self
+    }
+
+    /// Checks the thread against the enclave size
+    ///
+    /// This performs the alignment checks that `EADD` performs and verifies
+    /// that all offsets point into an enclave of `enclave_size` bytes. It
+    /// does not check the size of the SSA frames.
+    pub fn validate(&self, enclave_size: usize) -> Result<(), TcsError> {
+        let size = enclave_size as u64;
+        let page = |offset: u64| offset % PAGE == 0 && offset < size;
+
+        if TcsFlags::from_bits(self.flags.bits()).is_none() {
+            return Err(TcsError::InvalidFlags);
+        }
+
+        if self.oentry >= size {
+            return Err(TcsError::InvalidEntry);
+        }
+
+        if self.nssa == 0 || !page(self.ossa) {
+            return Err(TcsError::InvalidSsa);
+        }
+
+        if !page(self.ofsbase) {
+            return Err(TcsError::InvalidFsBase);
+        }
+
+        if !page(self.ogsbase) {
+            return Err(TcsError::InvalidGsBase);
+        }
+
+        Ok(())
+    }
+
+    /// The flags of the thread
+    pub const fn flags(&self) -> TcsFlags {
+        self.flags
+    }
+
+    /// The offset of the first SSA frame
+    pub const fn ossa(&self) -> u64 {
+        self.ossa
+    }
+
+    /// The index of the current SSA frame
+    pub const fn cssa(&self) -> u32 {
+        self.cssa
+    }
+
+    /// The number of SSA frames
+    pub const fn nssa(&self) -> u32 {
+        self.nssa
+    }
+
+    /// The offset of the entry point
+    pub const fn oentry(&self) -> u64 {
+        self.oentry
+    }
+
+    /// The offset of the FS base
+    pub const fn ofsbase(&self) -> u64 {
+        self.ofsbase
+    }
+
+    /// The offset of the GS base
+    pub const fn ogsbase(&self) -> u64 {
+        self.ogsbase
+    }
+
+    /// The FS segment limit
+    pub const fn fslimit(&self) -> u32 {
+        self.fslimit
+    }
+
+    /// The GS segment limit
+    pub const fn gslimit(&self) -> u32 {
+        self.gslimit
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Tcs, TcsError, TcsFlags};
+    use testaso::testaso;
+
+    testaso! {
+        struct Tcs: 4096, 4096 => {
+            reserved0: 0,
+            flags: 8,
+            ossa: 16,
+            cssa: 24,
+            nssa: 28,
+            oentry: 32,
+            reserved1: 40,
+            ofsbase: 48,
+            ogsbase: 56,
+            fslimit: 64,
+            gslimit: 68,
+            reserved2: 72
+        }
+    }
+
+    #[test]
+    fn validate() {
+        const SIZE: usize = 0x10000;
+
+        let tcs = Tcs::new(0x1000, 0x2000, 2).with_flags(TcsFlags::DBGOPTIN);
+        assert_eq!(tcs.validate(SIZE), Ok(()));
+        assert_eq!(tcs.as_ref()[8], 1);
+        assert_eq!(tcs.as_ref()[28], 2);
+
+        let tcs = tcs.with_fs(0x3000, 0).with_gs(0x4000, 0);
+        assert_eq!(tcs.validate(SIZE), Ok(()));
+        assert_eq!(tcs.ofsbase(), 0x3000);
+        assert_eq!(tcs.ogsbase(), 0x4000);
+
+        let bad = tcs.with_flags(TcsFlags::from_bits_retain(1 << 2));
+        assert_eq!(bad.validate(SIZE), Err(TcsError::InvalidFlags));
+
+        let bad = Tcs::new(SIZE as u64, 0x2000, 2);
+        assert_eq!(bad.validate(SIZE), Err(TcsError::InvalidEntry));
+
+        let bad = Tcs::new(0x1000, 0x2008, 2);
+        assert_eq!(bad.validate(SIZE), Err(TcsError::InvalidSsa));
+
+        let bad = Tcs::new(0x1000, 0x2000, 0);
+        assert_eq!(bad.validate(SIZE), Err(TcsError::InvalidSsa));
+
+        let bad = tcs.with_fs(0x3001, 0);
+        assert_eq!(bad.validate(SIZE), Err(TcsError::InvalidFsBase));
+
+        let bad = tcs.with_gs(SIZE as u64, 0);
+        assert_eq!(bad.validate(SIZE), Err(TcsError::InvalidGsBase));
+    }
+}
@@ -3,6 +3,7 @@
 bitflags::bitflags! {
     /// Expresses the non-XSAVE related enclave features
     #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct Features: u64 {
         /// Enclave has been initialized by EINIT
         ///
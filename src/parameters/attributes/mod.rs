@@ -12,8 +12,10 @@ use core::ops::*;
 /// This type represents the CPU features turned on in an enclave.
 #[repr(C, packed(4))]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Attributes {
     features: Features,
+    #[cfg_attr(feature = "serde", serde(with = "bitflags::serde"))]
     xfrm: Xfrm,
 }
 
@@ -48,6 +50,24 @@ impl Attributes {
     pub const fn xfrm(&self) -> Xfrm {
         self.xfrm
     }
+
+    /// Returns the union of two Attributes (a const `|`).
+    #[inline]
+    pub const fn union(self, other: Self) -> Self {
+        Self::new(
+            self.features.union(other.features),
+            self.xfrm.union(other.xfrm),
+        )
+    }
+
+    /// Returns the intersection of two Attributes (a const `&`).
+    #[inline]
+    pub const fn intersection(self, other: Self) -> Self {
+        Self::new(
+            self.features.intersection(other.features),
+            self.xfrm.intersection(other.xfrm),
+        )
+    }
 }
 
 impl Not for Attributes {
@@ -238,10 +258,21 @@ impl BitXorAssign<Xfrm> for Attributes {
 
 #[cfg(test)]
 mod test {
-    use super::Attributes;
+    use super::{Attributes, Features, Xfrm};
     use testaso::testaso;
 
     testaso! {
         struct Attributes: 4, 16 => {}
     }
+
+    #[test]
+    fn const_ops() {
+        const A: Attributes = Attributes::new(Features::MODE64BIT, Xfrm::X87);
+        const B: Attributes = Attributes::new(Features::DEBUG, Xfrm::X87.union(Xfrm::SSE));
+        const U: Attributes = A.union(B);
+        const I: Attributes = A.intersection(B);
+
+        assert_eq!(U, A | B);
+        assert_eq!(I, A & B);
+    }
 }
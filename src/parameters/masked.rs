@@ -7,11 +7,21 @@ use core::ops::{BitAnd, BitOr, Not};
 /// This type succinctly describes a masked type.
 #[repr(C)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Masked<T: BitAnd<Output = T>> {
     pub data: T,
     pub mask: T,
 }
 
+impl<T: BitAnd<Output = T>> Masked<T> {
+    /// Creates a new masked value
+    ///
+    /// Unlike `From<T>`, this is usable in constant expressions.
+    pub const fn new(data: T, mask: T) -> Self {
+        Self { data, mask }
+    }
+}
+
 impl<T> Default for Masked<T>
 where
     T: BitAnd<Output = T>,
@@ -23,12 +23,41 @@ bitflags::bitflags! {
     /// This type controls which extra data will be provided in the SSA page
     /// after an AEX.
     #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct MiscSelect: u32 {
         /// Report #PF and #GP information
         const EXINFO = 1 << 0;
     }
 }
 
+bitflags::bitflags! {
+    /// CET features for the enclave
+    ///
+    /// These are the `CET_ATTRIBUTES` of the enclave. They only take effect
+    /// if `Features::CET` is set.
+    #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct CetAttributes: u8 {
+        /// Enables shadow stacks (`SH_STK_EN`)
+        const SHADOW_STACK = 1 << 0;
+
+        /// Enables writes to shadow stacks with `WRSS` (`WR_SHSTK_EN`)
+        const WRITABLE_SHADOW_STACK = 1 << 1;
+
+        /// Enables indirect branch tracking (`ENDBR_EN`)
+        const ENDBRANCH = 1 << 2;
+
+        /// Enables the legacy code bitmap (`LEG_IW_EN`)
+        const LEGACY_BITMAP = 1 << 3;
+
+        /// Enables `NOTRACK` prefixed indirect branches (`NO_TRACK_EN`)
+        const NO_TRACK = 1 << 4;
+
+        /// Disables suppression of tracking (`SUPPRESS_DIS`)
+        const SUPPRESS_DISABLE = 1 << 5;
+    }
+}
+
 /// Enclave creation parameters
 ///
 /// This type is not specified in the Intel documentation and exists for
@@ -40,7 +69,8 @@ bitflags::bitflags! {
 /// the platform-supported features. Likewise, when creating a `Signature`
 /// the mask represents the required features for the enclave.
 #[repr(C)]
-#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Parameters {
     /// Choose info for the `Misc` section of the `StateSaveArea`
     pub misc: Masked<MiscSelect>,
@@ -48,10 +78,15 @@ pub struct Parameters {
     /// CPU features for the enclave
     pub attr: Masked<Attributes>,
 
+    /// CET features for the enclave (requires `Features::CET`)
+    pub cet: Masked<CetAttributes>,
+
     /// Extended ISV-defined family identifier
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     pub ext_fid: [u8; 16],
 
     /// Extended ISV-defined product identifier
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     pub ext_pid: [u8; 16],
 
     /// ISV-defined product identifier
@@ -59,4 +94,82 @@ pub struct Parameters {
 
     /// ISV-defined security version number
     pub svn: u16,
+
+    /// Loader-defined configuration identifier (requires `Features::KSS`)
+    ///
+    /// This value is only used when creating the `Secs`; it is not part of
+    /// the enclave signature.
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    pub configid: [u8; 64],
+
+    /// Security version number of `configid` (requires `Features::KSS`)
+    pub configsvn: u16,
+
+    /// Offset of the CET legacy code bitmap from the enclave base
+    ///
+    /// This value is only used when creating the `Secs` and only takes
+    /// effect with `CetAttributes::LEGACY_BITMAP`.
+    pub cet_legacy_bitmap: u64,
+}
+
+impl Default for Parameters {
+    #[inline]
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl Parameters {
+    /// The default parameters, usable in constant expressions
+    ///
+    /// This is what `Parameters::default()` returns. It is meant to be used with
+    /// struct update syntax when defining parameters in a `const` or `static`.
+    pub const DEFAULT: Self = Self {
+        misc: Masked::new(MiscSelect::empty(), MiscSelect::empty()),
+        attr: Masked::new(
+            Attributes::new(Features::empty(), Xfrm::empty()),
+            Attributes::new(Features::empty(), Xfrm::empty()),
+        ),
+        cet: Masked::new(CetAttributes::empty(), CetAttributes::empty()),
+        ext_fid: [0; 16],
+        ext_pid: [0; 16],
+        pid: 0,
+        svn: 0,
+        configid: [0; 64],
+        configsvn: 0,
+        cet_legacy_bitmap: 0,
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    static PARAMETERS: Parameters = Parameters {
+        attr: Masked::new(
+            Attributes::new(Features::MODE64BIT, Xfrm::X87.union(Xfrm::SSE)),
+            Attributes::new(Features::DEBUG.complement(), Xfrm::empty()),
+        ),
+        misc: Masked::new(MiscSelect::EXINFO, MiscSelect::empty()),
+        svn: 1,
+        ..Parameters::DEFAULT
+    };
+
+    #[test]
+    fn default() {
+        assert_eq!(PARAMETERS.pid, 0);
+        assert_eq!(PARAMETERS.svn, 1);
+    }
+
+    #[test]
+    fn cet() {
+        let cet = CetAttributes::SHADOW_STACK | CetAttributes::ENDBRANCH;
+        let parameters = Parameters {
+            cet: Masked::new(cet, CetAttributes::SHADOW_STACK),
+            ..PARAMETERS
+        };
+
+        let body = parameters.body([0; 32]);
+        assert_eq!(body.parameters(), parameters);
+    }
 }
@@ -73,6 +73,54 @@ impl<'a> SgxExtension<'a> {
     }
 }
 
+/// An allow list of platforms
+///
+/// Operators of a fixed fleet of machines can use this to reject PCK
+/// certificates (and therefore quotes) from unknown platforms.
+#[derive(Copy, Clone, Debug)]
+pub struct PlatformPolicy<'a> {
+    /// The allowed FMSPC values
+    pub fmspc: &'a [[u8; 6]],
+
+    /// The allowed PCEID values (any if `None`)
+    pub pceid: Option<&'a [[u8; 2]]>,
+}
+
+/// Error codes for `PlatformPolicy::check()`
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum PlatformPolicyError {
+    /// The FMSPC of the platform is not in the allowed list.
+    UnknownFmspc,
+    /// The PCEID of the platform is not in the allowed list.
+    UnknownPceid,
+}
+
+impl core::fmt::Display for PlatformPolicyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PlatformPolicyError::UnknownFmspc => write!(f, "SGX: FMSPC not allowed"),
+            PlatformPolicyError::UnknownPceid => write!(f, "SGX: PCEID not allowed"),
+        }
+    }
+}
+
+impl<'a> PlatformPolicy<'a> {
+    /// Checks the platform identified by a PCK certificate extension
+    pub fn check(&self, extension: &SgxExtension<'_>) -> Result<(), PlatformPolicyError> {
+        if !self.fmspc.iter().any(|f| f[..] == *extension.fmspc) {
+            return Err(PlatformPolicyError::UnknownFmspc);
+        }
+
+        match self.pceid {
+            Some(pceid) if !pceid.iter().any(|p| p[..] == *extension.pceid) => {
+                Err(PlatformPolicyError::UnknownPceid)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,4 +163,36 @@ mod tests {
         );
         assert!(extension.is_multi);
     }
+
+    #[test]
+    fn platform_policy() {
+        const PCK: &[u8] = include_bytes!("../../tests/single_pck.crt");
+        let pck: Certificate = Decode::from_der(PCK).unwrap();
+        let extensions = pck.tbs_certificate.extensions.unwrap();
+        let extension = SgxExtension::from_x509_extensions(&extensions).unwrap();
+
+        const FMSPC: [u8; 6] = [00, 0x70, 0x6E, 0x47, 00, 00];
+        const OTHER: [u8; 6] = [00, 0x60, 0x6A, 00, 00, 00];
+
+        let mut policy = PlatformPolicy {
+            fmspc: &[OTHER, FMSPC],
+            pceid: None,
+        };
+        assert_eq!(policy.check(&extension), Ok(()));
+
+        policy.pceid = Some(&[[0, 0]]);
+        assert_eq!(policy.check(&extension), Ok(()));
+
+        policy.pceid = Some(&[[0, 1]]);
+        assert_eq!(
+            policy.check(&extension),
+            Err(PlatformPolicyError::UnknownPceid)
+        );
+
+        policy.fmspc = &[OTHER];
+        assert_eq!(
+            policy.check(&extension),
+            Err(PlatformPolicyError::UnknownFmspc)
+        );
+    }
 }
@@ -0,0 +1,955 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! ECDSA quotes (versions 3 and 4)
+//!
+//! These are the quotes produced by the DCAP quoting enclave. The layout is
+//! described in the Intel SGX ECDSA Quote Library Reference. DCAP 1.15 and
+//! later can also produce version 4 quotes, as described in the Intel TDX DCAP
+//! Quoting Library API.
+
+use super::QuoteError;
+use crate::crypto::Digest;
+use crate::hex::Hex;
+use crate::{ReportBody, ReportData};
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], QuoteError> {
+        if self.0.len() < len {
+            return Err(QuoteError::InvalidLength);
+        }
+
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<&'a [u8; N], QuoteError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn body(&mut self) -> Result<&'a ReportBody, QuoteError> {
+        Ok(self.array::<384>()?.into())
+    }
+
+    fn u16(&mut self) -> Result<u16, QuoteError> {
+        Ok(u16::from_le_bytes(*self.array()?))
+    }
+
+    fn u32(&mut self) -> Result<u32, QuoteError> {
+        Ok(u32::from_le_bytes(*self.array()?))
+    }
+}
+
+/// A PCK certificate chain
+///
+/// Each certificate is a single PEM block borrowed from the quote's
+/// certification data, ready to be decoded by an X.509 library. Certification
+/// data of type 4 only contains the PCK (leaf) certificate.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PckChain<'a> {
+    /// The PCK certificate of the platform
+    pub leaf: &'a str,
+    /// The certificate of the PCK platform or processor CA (type 5 only)
+    pub intermediate: Option<&'a str>,
+    /// The Intel SGX root CA certificate (type 5 only)
+    pub root: Option<&'a str>,
+}
+
+impl<'a> PckChain<'a> {
+    const BEGIN: &'static str = "-----BEGIN CERTIFICATE-----";
+    const END: &'static str = "-----END CERTIFICATE-----";
+
+    // Returns the next PEM certificate and the remaining input.
+    fn next(pem: &'a str) -> Result<Option<(&'a str, &'a str)>, QuoteError> {
+        let pem = pem.trim_start();
+        if pem.is_empty() {
+            return Ok(None);
+        }
+
+        if !pem.starts_with(Self::BEGIN) {
+            return Err(QuoteError::InvalidCertData);
+        }
+
+        match pem.find(Self::END) {
+            Some(end) => Ok(Some(pem.split_at(end + Self::END.len()))),
+            None => Err(QuoteError::InvalidCertData),
+        }
+    }
+
+    fn parse(kind: u16, data: &'a [u8]) -> Result<Self, QuoteError> {
+        // The data is often NUL terminated.
+        let pem = core::str::from_utf8(data).map_err(|_| QuoteError::InvalidCertData)?;
+        let pem = pem.trim_end_matches('\0');
+
+        let mut certs = [None; 3];
+        let mut rest = pem;
+        let mut count = 0;
+        while let Some((cert, tail)) = Self::next(rest)? {
+            *certs.get_mut(count).ok_or(QuoteError::InvalidCertData)? = Some(cert);
+            count += 1;
+            rest = tail;
+        }
+
+        match (kind, count, certs) {
+            (4, 1, [Some(leaf), ..]) | (5, 3, [Some(leaf), ..]) => Ok(Self {
+                leaf,
+                intermediate: certs[1],
+                root: certs[2],
+            }),
+            (4 | 5, ..) => Err(QuoteError::InvalidCertData),
+            (kind, ..) => Err(QuoteError::UnsupportedCertDataType(kind)),
+        }
+    }
+}
+
+// Decodes a single PEM certificate.
+#[cfg(feature = "rcrypto")]
+fn decode_pem(pem: &str) -> Result<x509::Certificate, der::Error> {
+    use der::{pem::PemLabel, Decode};
+
+    // `DecodePem` can loop forever on truncated input, so decode the PEM
+    // block up front.
+    let (label, der) = der::pem::decode_vec(pem.as_bytes())?;
+    x509::Certificate::validate_pem_label(label)?;
+    x509::Certificate::from_der(&der)
+}
+
+#[cfg(feature = "rcrypto")]
+impl PckChain<'_> {
+    /// Decodes the PCK (leaf) certificate
+    ///
+    /// The SGX extension of the result can be read with
+    /// `pck::SgxExtension::from_x509_extensions()`.
+    pub fn leaf_certificate(&self) -> Result<x509::Certificate, der::Error> {
+        decode_pem(self.leaf)
+    }
+}
+
+/// An issuer certificate chain from an Intel PCS response header
+///
+/// The PCS returns the issuer chain of its responses in headers such as
+/// `SGX-PCK-Certificate-Issuer-Chain` or `TCB-Info-Issuer-Chain`. The value
+/// is the URL-encoded PEM of the issuing CA followed by the root CA.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IssuerChain<'a> {
+    /// The certificate of the issuing CA
+    pub issuer: &'a str,
+    /// The Intel SGX root CA certificate
+    pub root: &'a str,
+}
+
+impl<'a> IssuerChain<'a> {
+    /// Parses a header value, percent-decoding it into `buf`
+    ///
+    /// The decoded chain is never longer than `header`, so a buffer of the
+    /// same length always suffices.
+    pub fn parse(header: &str, buf: &'a mut [u8]) -> Result<Self, QuoteError> {
+        let hex = |c: u8| match c {
+            b'0'..=b'9' => Ok(c - b'0'),
+            b'a'..=b'f' => Ok(c - b'a' + 10),
+            b'A'..=b'F' => Ok(c - b'A' + 10),
+            _ => Err(QuoteError::InvalidCertData),
+        };
+
+        let mut len = 0;
+        let mut input = header.as_bytes().iter();
+        while let Some(&c) = input.next() {
+            let byte = match c {
+                b'%' => match (input.next(), input.next()) {
+                    (Some(&h), Some(&l)) => hex(h)? << 4 | hex(l)?,
+                    _ => return Err(QuoteError::InvalidCertData),
+                },
+                c => c,
+            };
+
+            *buf.get_mut(len).ok_or(QuoteError::InvalidLength)? = byte;
+            len += 1;
+        }
+
+        let pem = core::str::from_utf8(&buf[..len]).map_err(|_| QuoteError::InvalidCertData)?;
+        let (issuer, rest) = PckChain::next(pem)?.ok_or(QuoteError::InvalidCertData)?;
+        let (root, rest) = PckChain::next(rest)?.ok_or(QuoteError::InvalidCertData)?;
+        if PckChain::next(rest)?.is_some() {
+            return Err(QuoteError::InvalidCertData);
+        }
+
+        Ok(Self { issuer, root })
+    }
+
+    /// Decodes the issuer and root certificates
+    #[cfg(feature = "rcrypto")]
+    pub fn certificates(&self) -> Result<[x509::Certificate; 2], der::Error> {
+        Ok([decode_pem(self.issuer)?, decode_pem(self.root)?])
+    }
+}
+
+/// Platform identity from certification data of type 1, 2 or 3
+///
+/// This is the `PPID || CPUSVN || PCESVN || PCEID` blob used to look up the
+/// PCK certificate of a platform. For type 1 the 16-byte PPID is in the
+/// clear. For types 2 and 3 it is encrypted with RSA-2048-OAEP or
+/// RSA-3072-OAEP respectively and can be decrypted by the holder of the
+/// provisioning key with `crypto::OaepDecrypt::decrypt()`.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct PpidInfo<'a> {
+    pub ppid: &'a [u8],
+    pub cpusvn: [u8; 16],
+    pub pcesvn: u16,
+    pub pceid: u16,
+}
+
+impl core::fmt::Debug for PpidInfo<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PpidInfo")
+            .field("ppid", &Hex(self.ppid))
+            .field("cpusvn", &Hex(&self.cpusvn))
+            .field("pcesvn", &self.pcesvn)
+            .field("pceid", &self.pceid)
+            .finish()
+    }
+}
+
+impl<'a> PpidInfo<'a> {
+    fn parse(kind: u16, data: &'a [u8]) -> Result<Self, QuoteError> {
+        let len = match kind {
+            1 => 16,
+            2 => 256,
+            3 => 384,
+            kind => return Err(QuoteError::UnsupportedCertDataType(kind)),
+        };
+
+        if data.len() != len + 16 + 2 + 2 {
+            return Err(QuoteError::InvalidCertData);
+        }
+
+        let mut reader = Reader(data);
+        Ok(Self {
+            ppid: reader.take(len)?,
+            cpusvn: *reader.array()?,
+            pcesvn: reader.u16()?,
+            pceid: reader.u16()?,
+        })
+    }
+
+    /// Whether `ppid` is encrypted (certification data type 2 or 3)
+    pub fn is_encrypted(&self) -> bool {
+        self.ppid.len() != 16
+    }
+}
+
+/// The signature section of an ECDSA quote
+///
+/// | Offset | Size | Field               |
+/// |--------|------|---------------------|
+/// | 0      | 64   | report_signature    |
+/// | 64     | 64   | attestation_key     |
+/// | 128    | 384  | qe_report           |
+/// | 512    | 64   | qe_report_signature |
+/// | 576    | 2    | qe_auth_data_len    |
+/// | 578    | *    | qe_auth_data        |
+/// | *      | 2    | cert_data_type      |
+/// | *      | 4    | cert_data_len       |
+/// | *      | *    | cert_data           |
+///
+/// Signatures and keys are raw big-endian P-256 values (`r || s` and
+/// `x || y`, respectively).
+///
+/// In version 4 quotes, the fields from `qe_report` on are wrapped in
+/// certification data of type 6 (QE report certification data). The wrapper
+/// is removed while parsing, so both versions are accessed the same way.
+#[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct QuoteSignature<'a> {
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    report_signature: &'a [u8; 64],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    attestation_key: &'a [u8; 64],
+    qe_report: &'a ReportBody,
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    qe_report_signature: &'a [u8; 64],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    qe_auth_data: &'a [u8],
+    cert_data_type: u16,
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    cert_data: &'a [u8],
+}
+
+impl core::fmt::Debug for QuoteSignature<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("QuoteSignature")
+            .field("report_signature", &Hex(self.report_signature))
+            .field("attestation_key", &Hex(self.attestation_key))
+            .field("qe_report", &self.qe_report)
+            .field("qe_report_signature", &Hex(self.qe_report_signature))
+            .field("qe_auth_data", &Hex(self.qe_auth_data))
+            .field("cert_data_type", &self.cert_data_type)
+            .field("cert_data", &Hex(self.cert_data))
+            .finish()
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for QuoteSignature<'a> {
+    type Error = QuoteError;
+
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = Reader(bytes);
+
+        let report_signature = reader.array()?;
+        let attestation_key = reader.array()?;
+        Self::parse(report_signature, attestation_key, reader.0)
+    }
+}
+
+impl<'a> QuoteSignature<'a> {
+    // Certification data type of the QE report certification data
+    const QE_REPORT_CERT_DATA: u16 = 6;
+
+    // Parses the signature section of a version 4 quote.
+    fn parse_v4(bytes: &'a [u8]) -> Result<Self, QuoteError> {
+        let mut reader = Reader(bytes);
+
+        let report_signature = reader.array()?;
+        let attestation_key = reader.array()?;
+
+        match reader.u16()? {
+            Self::QE_REPORT_CERT_DATA => (),
+            t => return Err(QuoteError::UnsupportedCertDataType(t)),
+        }
+
+        let len = reader.u32()?;
+        if reader.0.len() != len as usize {
+            return Err(QuoteError::InvalidLength);
+        }
+
+        Self::parse(report_signature, attestation_key, reader.0)
+    }
+
+    // Parses the fields following the attestation key.
+    fn parse(
+        report_signature: &'a [u8; 64],
+        attestation_key: &'a [u8; 64],
+        bytes: &'a [u8],
+    ) -> Result<Self, QuoteError> {
+        let mut reader = Reader(bytes);
+
+        let qe_report = reader.body()?;
+        let qe_report_signature = reader.array()?;
+
+        let len = reader.u16()?;
+        let qe_auth_data = reader.take(len.into())?;
+
+        let cert_data_type = reader.u16()?;
+        let len = reader.u32()?;
+        let cert_data = reader.take(len as usize)?;
+
+        if !reader.0.is_empty() {
+            return Err(QuoteError::InvalidLength);
+        }
+
+        Ok(Self {
+            report_signature,
+            attestation_key,
+            qe_report,
+            qe_report_signature,
+            qe_auth_data,
+            cert_data_type,
+            cert_data,
+        })
+    }
+
+    /// Signature over the quote header and report body
+    pub fn report_signature(&self) -> &'a [u8; 64] {
+        self.report_signature
+    }
+
+    /// Public attestation key that produced `report_signature()`
+    pub fn attestation_key(&self) -> &'a [u8; 64] {
+        self.attestation_key
+    }
+
+    /// Report body of the quoting enclave
+    pub fn qe_report(&self) -> &'a ReportBody {
+        self.qe_report
+    }
+
+    /// Signature over `qe_report()` by the PCK
+    pub fn qe_report_signature(&self) -> &'a [u8; 64] {
+        self.qe_report_signature
+    }
+
+    /// Authentication data bound into the quoting enclave's report data
+    pub fn qe_auth_data(&self) -> &'a [u8] {
+        self.qe_auth_data
+    }
+
+    /// Checks that the QE report binds the attestation key
+    ///
+    /// The `reportdata` of the QE report must be the SHA2-256 hash of the
+    /// attestation key and the QE authentication data (see `ReportData`).
+    /// This does not check the signature over the QE report.
+    pub fn qe_binds_attestation_key<D: Digest<Output = [u8; 32]>>(&self) -> bool {
+        ReportData::bind_pubkey_and_nonce::<D>(self.attestation_key, self.qe_auth_data)
+            .matches(self.qe_report)
+    }
+
+    /// Type of the certification data (5 for a PEM PCK certificate chain)
+    pub fn cert_data_type(&self) -> u16 {
+        self.cert_data_type
+    }
+
+    /// Certification data used to verify `qe_report_signature()`
+    pub fn cert_data(&self) -> &'a [u8] {
+        self.cert_data
+    }
+
+    /// The PCK certificate chain from the certification data
+    ///
+    /// This supports certification data of type 4 (PCK certificate) and
+    /// type 5 (PCK certificate chain), both of which are PEM encoded.
+    pub fn pck_chain(&self) -> Result<PckChain<'a>, QuoteError> {
+        PckChain::parse(self.cert_data_type, self.cert_data)
+    }
+
+    /// Parses the platform identity from the certification data
+    ///
+    /// This fails with `QuoteError::UnsupportedCertDataType` unless the
+    /// certification data type is 1, 2 or 3.
+    pub fn ppid_info(&self) -> Result<PpidInfo<'a>, QuoteError> {
+        PpidInfo::parse(self.cert_data_type, self.cert_data)
+    }
+}
+
+/// An ECDSA quote
+///
+/// This type wraps the `sgx_quote_3_t` structure from the Intel SGX DCAP
+/// library, or its version 4 successor for SGX enclaves:
+///
+/// | Offset | Size | Field          |
+/// |--------|------|----------------|
+/// | 0      | 2    | version        |
+/// | 2      | 2    | att_key_type   |
+/// | 4      | 4    | tee_type       |
+/// | 8      | 2    | qe_svn         |
+/// | 10     | 2    | pce_svn        |
+/// | 12     | 16   | qe_vendor_id   |
+/// | 28     | 20   | user_data      |
+/// | 48     | 384  | report_body    |
+/// | 432    | 4    | signature_len  |
+/// | 436    | *    | signature      |
+///
+/// The `tee_type` field is reserved in version 3. Version 4 quotes of TDX
+/// guests (which carry a TD report instead of an SGX report body) are
+/// rejected with `QuoteError::UnsupportedTeeType`.
+///
+/// All fields borrow from the input; nothing is copied.
+///
+/// With the `serde` feature this type can be serialized, but since it borrows
+/// from the raw quote it cannot be deserialized; parse the bytes instead.
+#[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct QuoteRef<'a> {
+    #[cfg_attr(feature = "serde", serde(skip))]
+    signed: &'a [u8],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    header: &'a [u8; 48],
+    body: &'a ReportBody,
+    signature: QuoteSignature<'a>,
+}
+
+impl core::fmt::Debug for QuoteRef<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("QuoteRef")
+            .field("header", &Hex(self.header))
+            .field("body", &self.body)
+            .field("signature", &self.signature)
+            .finish()
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for QuoteRef<'a> {
+    type Error = QuoteError;
+
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = Reader(bytes);
+
+        let header: &[u8; 48] = reader.array()?;
+
+        let version = u16::from_le_bytes([header[0], header[1]]);
+        match version {
+            Self::VERSION | Self::VERSION_4 => (),
+            v => return Err(QuoteError::UnsupportedVersion(v)),
+        }
+
+        match u16::from_le_bytes([header[2], header[3]]) {
+            Self::ECDSA_256 => (),
+            t => return Err(QuoteError::UnknownSignType(t)),
+        }
+
+        // The body of a TDX quote is a TD report, not an SGX report body.
+        let tee_type = u32::from_le_bytes(header[4..8].try_into().unwrap());
+        if version == Self::VERSION_4 && tee_type != Self::TEE_SGX {
+            return Err(QuoteError::UnsupportedTeeType(tee_type));
+        }
+
+        let body = reader.body()?;
+        let len = reader.u32()?;
+        if reader.0.len() != len as usize {
+            return Err(QuoteError::InvalidLength);
+        }
+
+        let signature = match version {
+            Self::VERSION_4 => QuoteSignature::parse_v4(reader.0)?,
+            _ => reader.0.try_into()?,
+        };
+
+        Ok(Self {
+            signed: &bytes[..Self::BODY_END],
+            header,
+            body,
+            signature,
+        })
+    }
+}
+
+impl<'a> QuoteRef<'a> {
+    /// Quote version 3
+    pub const VERSION: u16 = 3;
+
+    /// Quote version 4
+    pub const VERSION_4: u16 = 4;
+
+    /// TEE type of SGX enclaves in version 4 quotes
+    pub const TEE_SGX: u32 = 0x00;
+
+    /// TEE type of TDX guests in version 4 quotes
+    pub const TEE_TDX: u32 = 0x81;
+
+    /// Attestation key type for ECDSA-256-with-P-256
+    pub const ECDSA_256: u16 = 2;
+
+    /// Vendor ID of Intel's quoting enclave (`939A7233-F79C-4CA9-940A-0DB3957F0607`)
+    pub const INTEL_QE_VENDOR_ID: [u8; 16] = [
+        0x93, 0x9a, 0x72, 0x33, 0xf7, 0x9c, 0x4c, 0xa9, 0x94, 0x0a, 0x0d, 0xb3, 0x95, 0x7f, 0x06,
+        0x07,
+    ];
+
+    const BODY_END: usize = 48 + 384;
+
+    fn u16_at(&self, offset: usize) -> u16 {
+        u16::from_le_bytes([self.header[offset], self.header[offset + 1]])
+    }
+
+    /// Version of the quote structure
+    pub fn version(&self) -> u16 {
+        self.u16_at(0)
+    }
+
+    /// Type of the attestation key
+    pub fn att_key_type(&self) -> u16 {
+        self.u16_at(2)
+    }
+
+    /// Type of the TEE that produced the quote (reserved in version 3)
+    pub fn tee_type(&self) -> u32 {
+        u32::from_le_bytes(self.header[4..8].try_into().unwrap())
+    }
+
+    /// Security version number of the quoting enclave
+    pub fn qe_svn(&self) -> u16 {
+        self.u16_at(8)
+    }
+
+    /// Security version number of the provisioning certification enclave
+    pub fn pce_svn(&self) -> u16 {
+        self.u16_at(10)
+    }
+
+    /// Vendor of the quoting enclave
+    pub fn qe_vendor_id(&self) -> &'a [u8; 16] {
+        self.header[12..28].try_into().unwrap()
+    }
+
+    /// Whether the quote was produced by Intel's quoting enclave
+    pub fn is_intel_qe(&self) -> bool {
+        *self.qe_vendor_id() == Self::INTEL_QE_VENDOR_ID
+    }
+
+    /// Checks that the quote was produced by Intel's quoting enclave
+    ///
+    /// Verifiers that rely on Intel's certificates should call this first, so
+    /// that a foreign quote fails with `QuoteError::UnknownQeVendor` rather
+    /// than later during certificate checks.
+    pub fn check_intel_qe(&self) -> Result<(), QuoteError> {
+        if !self.is_intel_qe() {
+            return Err(QuoteError::UnknownQeVendor);
+        }
+
+        Ok(())
+    }
+
+    /// Custom data supplied by the quoting enclave
+    pub fn user_data(&self) -> &'a [u8; 20] {
+        self.header[28..48].try_into().unwrap()
+    }
+
+    /// Report body of the quoted enclave
+    pub fn report_body(&self) -> &'a ReportBody {
+        self.body
+    }
+
+    /// The header and report body, as covered by `report_signature()`
+    pub fn signed_data(&self) -> &'a [u8] {
+        self.signed
+    }
+
+    /// The signature section of the quote
+    pub fn signature(&self) -> &QuoteSignature<'a> {
+        &self.signature
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const CERT: &str = "-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----";
+
+    fn encode(auth: &[u8], cert: &[u8]) -> std::vec::Vec<u8> {
+        encode_with(auth, 5, cert)
+    }
+
+    fn encode_with(auth: &[u8], kind: u16, cert: &[u8]) -> std::vec::Vec<u8> {
+        let mut sig = std::vec![0u8; 576];
+        sig[..64].copy_from_slice(&[0x51; 64]);
+        sig[64..128].copy_from_slice(&[0x4b; 64]);
+        sig[128 + 64..128 + 96].copy_from_slice(&[0x9e; 32]);
+        sig[512..576].copy_from_slice(&[0x52; 64]);
+        sig.extend_from_slice(&(auth.len() as u16).to_le_bytes());
+        sig.extend_from_slice(auth);
+        sig.extend_from_slice(&kind.to_le_bytes());
+        sig.extend_from_slice(&(cert.len() as u32).to_le_bytes());
+        sig.extend_from_slice(cert);
+
+        let mut bytes = std::vec![0u8; QuoteRef::BODY_END];
+        bytes[0] = 3;
+        bytes[2] = 2;
+        bytes[8] = 7;
+        bytes[10] = 9;
+        bytes[12..28].copy_from_slice(&[0xaa; 16]);
+        bytes[28..48].copy_from_slice(&[0xbb; 20]);
+        bytes[48 + 64..48 + 96].copy_from_slice(&[0xee; 32]);
+        bytes.extend_from_slice(&(sig.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&sig);
+        bytes
+    }
+
+    #[test]
+    fn parse() {
+        let bytes = encode(&[1, 2], b"cert");
+        let quote = QuoteRef::try_from(&bytes[..]).unwrap();
+        assert_eq!(quote.version(), 3);
+        assert_eq!(quote.att_key_type(), QuoteRef::ECDSA_256);
+        assert_eq!(quote.qe_svn(), 7);
+        assert_eq!(quote.pce_svn(), 9);
+        assert_eq!(quote.qe_vendor_id(), &[0xaa; 16]);
+        assert!(!quote.is_intel_qe());
+        assert_eq!(quote.check_intel_qe(), Err(QuoteError::UnknownQeVendor));
+        assert_eq!(quote.user_data(), &[0xbb; 20]);
+        assert_eq!(quote.report_body().mrenclave, [0xee; 32]);
+        assert_eq!(quote.signed_data(), &bytes[..432]);
+
+        let sig = quote.signature();
+        assert_eq!(sig.report_signature(), &[0x51; 64]);
+        assert_eq!(sig.attestation_key(), &[0x4b; 64]);
+        assert_eq!(sig.qe_report().mrenclave, [0x9e; 32]);
+        assert_eq!(sig.qe_report_signature(), &[0x52; 64]);
+        assert_eq!(sig.qe_auth_data(), &[1, 2]);
+        assert_eq!(sig.cert_data_type(), 5);
+        assert_eq!(sig.cert_data(), b"cert");
+
+        let mut intel = bytes.clone();
+        intel[12..28].copy_from_slice(&QuoteRef::INTEL_QE_VENDOR_ID);
+        let intel = QuoteRef::try_from(&intel[..]).unwrap();
+        assert!(intel.is_intel_qe());
+        assert_eq!(intel.check_intel_qe(), Ok(()));
+    }
+
+    // Converts a version 3 quote to version 4 by wrapping the QE report.
+    fn to_v4(v3: &[u8]) -> std::vec::Vec<u8> {
+        let (head, sig) = v3.split_at(QuoteRef::BODY_END + 4);
+        let (keys, qe) = sig.split_at(128);
+
+        let mut bytes = head.to_vec();
+        bytes[0] = 4;
+        bytes.extend_from_slice(keys);
+        bytes.extend_from_slice(&6u16.to_le_bytes());
+        bytes.extend_from_slice(&(qe.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(qe);
+
+        let len = (bytes.len() - head.len()) as u32;
+        bytes[QuoteRef::BODY_END..][..4].copy_from_slice(&len.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn v4() {
+        let v3 = encode(&[1, 2], b"cert");
+        let bytes = to_v4(&v3);
+        let quote = QuoteRef::try_from(&bytes[..]).unwrap();
+        assert_eq!(quote.version(), QuoteRef::VERSION_4);
+        assert_eq!(quote.tee_type(), QuoteRef::TEE_SGX);
+        assert_eq!(quote.qe_svn(), 7);
+        assert_eq!(quote.report_body().mrenclave, [0xee; 32]);
+        assert_eq!(quote.signed_data(), &bytes[..432]);
+
+        let sig = quote.signature();
+        let expected = QuoteRef::try_from(&v3[..]).unwrap();
+        let expected = expected.signature();
+        assert_eq!(sig.report_signature(), expected.report_signature());
+        assert_eq!(sig.attestation_key(), expected.attestation_key());
+        assert_eq!(sig.qe_report().mrenclave, [0x9e; 32]);
+        assert_eq!(sig.qe_report_signature(), expected.qe_report_signature());
+        assert_eq!(sig.qe_auth_data(), &[1, 2]);
+        assert_eq!(sig.cert_data_type(), 5);
+        assert_eq!(sig.cert_data(), b"cert");
+
+        let mut tdx = bytes.clone();
+        tdx[4] = 0x81;
+        assert_eq!(
+            QuoteRef::try_from(&tdx[..]).unwrap_err(),
+            QuoteError::UnsupportedTeeType(QuoteRef::TEE_TDX)
+        );
+
+        // The QE report must be wrapped in certification data of type 6.
+        let mut wrapped = bytes.clone();
+        wrapped[QuoteRef::BODY_END + 4 + 128] = 5;
+        assert_eq!(
+            QuoteRef::try_from(&wrapped[..]).unwrap_err(),
+            QuoteError::UnsupportedCertDataType(5)
+        );
+
+        let mut wrapped = bytes.clone();
+        wrapped[QuoteRef::BODY_END + 4 + 130] += 1;
+        assert_eq!(
+            QuoteRef::try_from(&wrapped[..]).unwrap_err(),
+            QuoteError::InvalidLength
+        );
+
+        // A version 3 quote is not parsed with the version 4 layout.
+        let mut v3 = v3;
+        v3[0] = 4;
+        assert!(QuoteRef::try_from(&v3[..]).is_err());
+    }
+
+    #[test]
+    fn invalid() {
+        let bytes = encode(&[], &[]);
+        for len in [0, 431, 435, bytes.len() - 1] {
+            assert_eq!(
+                QuoteRef::try_from(&bytes[..len]).unwrap_err(),
+                QuoteError::InvalidLength
+            );
+        }
+
+        let mut bytes = encode(&[], &[]);
+        bytes.push(0);
+        assert_eq!(
+            QuoteRef::try_from(&bytes[..]).unwrap_err(),
+            QuoteError::InvalidLength
+        );
+
+        // Claim more certification data than the signature section holds.
+        let mut bytes = encode(&[], &[]);
+        let end = bytes.len();
+        bytes[end - 4] = 1;
+        assert_eq!(
+            QuoteRef::try_from(&bytes[..]).unwrap_err(),
+            QuoteError::InvalidLength
+        );
+
+        let mut bytes = encode(&[], &[]);
+        bytes[0] = 5;
+        assert_eq!(
+            QuoteRef::try_from(&bytes[..]).unwrap_err(),
+            QuoteError::UnsupportedVersion(5)
+        );
+
+        let mut bytes = encode(&[], &[]);
+        bytes[2] = 3;
+        assert_eq!(
+            QuoteRef::try_from(&bytes[..]).unwrap_err(),
+            QuoteError::UnknownSignType(3)
+        );
+    }
+
+    #[test]
+    fn pck_chain() {
+        let chain = std::format!("{CERT}\n{CERT}\n{CERT}\n\0");
+        let bytes = encode(&[], chain.as_bytes());
+        let quote = QuoteRef::try_from(&bytes[..]).unwrap();
+        let chain = quote.signature().pck_chain().unwrap();
+        assert_eq!(chain.leaf, CERT);
+        assert_eq!(chain.intermediate, Some(CERT));
+        assert_eq!(chain.root, Some(CERT));
+
+        let bytes = encode_with(&[], 4, CERT.as_bytes());
+        let quote = QuoteRef::try_from(&bytes[..]).unwrap();
+        let chain = quote.signature().pck_chain().unwrap();
+        assert_eq!(chain.leaf, CERT);
+        assert_eq!(chain.intermediate, None);
+        assert_eq!(chain.root, None);
+
+        let invalid = [
+            (5, CERT.as_bytes()),
+            (4, &b"junk"[..]),
+            (5, &CERT.as_bytes()[..40]),
+        ];
+        for (kind, data) in invalid {
+            let bytes = encode_with(&[], kind, data);
+            let quote = QuoteRef::try_from(&bytes[..]).unwrap();
+            assert_eq!(
+                quote.signature().pck_chain().unwrap_err(),
+                QuoteError::InvalidCertData
+            );
+        }
+
+        let bytes = encode_with(&[], 6, CERT.as_bytes());
+        let quote = QuoteRef::try_from(&bytes[..]).unwrap();
+        assert_eq!(
+            quote.signature().pck_chain().unwrap_err(),
+            QuoteError::UnsupportedCertDataType(6)
+        );
+    }
+
+    // Percent-encodes everything but unreserved characters.
+    fn url_encode(value: &str) -> std::string::String {
+        value
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    std::string::String::from(b as char)
+                }
+                b => std::format!("%{b:02X}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn issuer_chain() {
+        let header = url_encode(&std::format!("{CERT}\n{CERT}\n"));
+        assert!(header.contains("%20CERTIFICATE"));
+
+        let mut buf = std::vec![0u8; header.len()];
+        let chain = IssuerChain::parse(&header, &mut buf).unwrap();
+        assert_eq!(chain.issuer, CERT);
+        assert_eq!(chain.root, CERT);
+
+        let mut buf = [0u8; 16];
+        assert_eq!(
+            IssuerChain::parse(&header, &mut buf).unwrap_err(),
+            QuoteError::InvalidLength
+        );
+
+        let mut buf = [0u8; 1024];
+        let invalid = [
+            url_encode(CERT),
+            url_encode(&std::format!("{CERT}{CERT}{CERT}")),
+            std::format!("{}%2", url_encode(CERT)),
+            std::format!("{}%zz", url_encode(CERT)),
+            std::string::String::from("%FF%FE"),
+        ];
+        for header in invalid {
+            assert_eq!(
+                IssuerChain::parse(&header, &mut buf).unwrap_err(),
+                QuoteError::InvalidCertData
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "rcrypto")]
+    fn issuer_chain_certificates() {
+        const PEM: &str = include_str!("../../tests/single_pck.pem");
+
+        let header = url_encode(&std::format!("{PEM}{PEM}"));
+        let mut buf = std::vec![0u8; header.len()];
+        let chain = IssuerChain::parse(&header, &mut buf).unwrap();
+        let [issuer, root] = chain.certificates().unwrap();
+        assert_eq!(issuer, root);
+    }
+
+    #[test]
+    #[cfg(feature = "rcrypto")]
+    fn leaf_certificate() {
+        use der::Decode;
+
+        const PEM: &str = include_str!("../../tests/single_pck.pem");
+        const DER: &[u8] = include_bytes!("../../tests/single_pck.crt");
+
+        let bytes = encode_with(&[], 4, PEM.as_bytes());
+        let quote = QuoteRef::try_from(&bytes[..]).unwrap();
+        let chain = quote.signature().pck_chain().unwrap();
+        let cert = chain.leaf_certificate().unwrap();
+        assert_eq!(cert, x509::Certificate::from_der(DER).unwrap());
+
+        let bytes = encode_with(&[], 4, CERT.as_bytes());
+        let quote = QuoteRef::try_from(&bytes[..]).unwrap();
+        let chain = quote.signature().pck_chain().unwrap();
+        assert!(chain.leaf_certificate().is_err());
+    }
+
+    #[test]
+    fn ppid_info() {
+        for (kind, len) in [(1, 16), (2, 256), (3, 384)] {
+            let mut data = std::vec![0xaa; len];
+            data.extend_from_slice(&[0xcc; 16]);
+            data.extend_from_slice(&7u16.to_le_bytes());
+            data.extend_from_slice(&9u16.to_le_bytes());
+
+            let bytes = encode_with(&[], kind, &data);
+            let quote = QuoteRef::try_from(&bytes[..]).unwrap();
+            let info = quote.signature().ppid_info().unwrap();
+            assert_eq!(info.ppid, &data[..len]);
+            assert_eq!(info.cpusvn, [0xcc; 16]);
+            assert_eq!(info.pcesvn, 7);
+            assert_eq!(info.pceid, 9);
+            assert_eq!(info.is_encrypted(), kind != 1);
+
+            let bytes = encode_with(&[], kind, &data[1..]);
+            let quote = QuoteRef::try_from(&bytes[..]).unwrap();
+            assert_eq!(
+                quote.signature().ppid_info().unwrap_err(),
+                QuoteError::InvalidCertData
+            );
+        }
+
+        let bytes = encode(&[], CERT.as_bytes());
+        let quote = QuoteRef::try_from(&bytes[..]).unwrap();
+        assert_eq!(
+            quote.signature().ppid_info().unwrap_err(),
+            QuoteError::UnsupportedCertDataType(5)
+        );
+    }
+
+    #[cfg(feature = "rcrypto")]
+    #[test]
+    fn qe_binding() {
+        use crate::crypto::rcrypto::S256Digest;
+        use crate::ReportData;
+
+        // The QE report data follows the quote body, the signature length,
+        // the two signatures and the first 320 bytes of the QE report.
+        const OFFSET: usize = QuoteRef::BODY_END + 4 + 128 + 320;
+
+        let mut bytes = encode(&[1, 2], b"cert");
+        let quote = QuoteRef::try_from(&bytes[..]).unwrap();
+        assert!(!quote.signature().qe_binds_attestation_key::<S256Digest>());
+
+        let data = ReportData::bind_pubkey_and_nonce::<S256Digest>(&[0x4b; 64], &[1, 2]);
+        bytes[OFFSET..OFFSET + 64].copy_from_slice(data.as_ref());
+        let quote = QuoteRef::try_from(&bytes[..]).unwrap();
+        assert!(quote.signature().qe_binds_attestation_key::<S256Digest>());
+    }
+}
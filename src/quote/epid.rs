@@ -0,0 +1,230 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Legacy EPID quotes (version 2)
+//!
+//! EPID attestation has reached end of life. These types only exist so that
+//! archived quotes can be inspected; they do not verify anything.
+
+#![allow(deprecated)]
+
+use super::QuoteError;
+use crate::hex::Hex;
+use crate::ReportBody;
+
+/// The EPID signature type
+#[deprecated(note = "EPID attestation has reached end of life")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum EpidSignType {
+    Unlinkable,
+    Linkable,
+}
+
+/// A version 2 EPID quote
+///
+/// This type wraps the `sgx_quote_t` structure from the Intel SGX SDK:
+///
+/// | Offset | Size | Field          |
+/// |--------|------|----------------|
+/// | 0      | 2    | version        |
+/// | 2      | 2    | sign_type      |
+/// | 4      | 4    | epid_group_id  |
+/// | 8      | 2    | qe_svn         |
+/// | 10     | 2    | pce_svn        |
+/// | 12     | 4    | xeid           |
+/// | 16     | 32   | basename       |
+/// | 48     | 384  | report_body    |
+/// | 432    | 4    | signature_len  |
+/// | 436    | *    | signature      |
+///
+/// Intel Attestation Service reports carry only the first 432 bytes (without
+/// the signature), so that form is accepted as well.
+///
+/// With the `serde` feature this type can be serialized, but since it borrows
+/// from the raw quote it cannot be deserialized; parse the bytes instead.
+#[deprecated(note = "EPID attestation has reached end of life")]
+#[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EpidQuote<'a> {
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    header: &'a [u8; 48],
+    body: &'a ReportBody,
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    signature: &'a [u8],
+}
+
+impl core::fmt::Debug for EpidQuote<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("EpidQuote")
+            .field("header", &Hex(self.header))
+            .field("body", &self.body)
+            .field("signature", &Hex(self.signature))
+            .finish()
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for EpidQuote<'a> {
+    type Error = QuoteError;
+
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
+        if bytes.len() < Self::BODY_END {
+            return Err(QuoteError::InvalidLength);
+        }
+
+        let (head, tail) = bytes.split_at(Self::BODY_END);
+        let signature = match tail.len() {
+            0 => tail,
+            1..=3 => return Err(QuoteError::InvalidLength),
+            _ => {
+                let (len, signature) = tail.split_at(4);
+                let len = u32::from_le_bytes(len.try_into().unwrap());
+                if signature.len() != len as usize {
+                    return Err(QuoteError::InvalidLength);
+                }
+                signature
+            }
+        };
+
+        let quote = Self {
+            header: head[..48].try_into().unwrap(),
+            body: <&[u8; 384]>::try_from(&head[48..]).unwrap().into(),
+            signature,
+        };
+
+        match quote.version() {
+            Self::VERSION => (),
+            v => return Err(QuoteError::UnsupportedVersion(v)),
+        }
+
+        match u16::from_le_bytes([quote.header[2], quote.header[3]]) {
+            0 | 1 => Ok(quote),
+            t => Err(QuoteError::UnknownSignType(t)),
+        }
+    }
+}
+
+impl<'a> EpidQuote<'a> {
+    /// The only supported quote version
+    pub const VERSION: u16 = 2;
+
+    const BODY_END: usize = 48 + 384;
+
+    fn u16_at(&self, offset: usize) -> u16 {
+        u16::from_le_bytes([self.header[offset], self.header[offset + 1]])
+    }
+
+    /// Version of the quote structure
+    pub fn version(&self) -> u16 {
+        self.u16_at(0)
+    }
+
+    /// Whether the EPID signature is linkable
+    pub fn sign_type(&self) -> EpidSignType {
+        match self.u16_at(2) {
+            0 => EpidSignType::Unlinkable,
+            _ => EpidSignType::Linkable,
+        }
+    }
+
+    /// EPID group of the platform
+    pub fn epid_group_id(&self) -> [u8; 4] {
+        self.header[4..8].try_into().unwrap()
+    }
+
+    /// Security version number of the quoting enclave
+    pub fn qe_svn(&self) -> u16 {
+        self.u16_at(8)
+    }
+
+    /// Security version number of the provisioning certification enclave
+    pub fn pce_svn(&self) -> u16 {
+        self.u16_at(10)
+    }
+
+    /// Extended EPID group ID
+    pub fn xeid(&self) -> u32 {
+        u32::from_le_bytes(self.header[12..16].try_into().unwrap())
+    }
+
+    /// Basename used for linkable signatures
+    pub fn basename(&self) -> &'a [u8; 32] {
+        self.header[16..48].try_into().unwrap()
+    }
+
+    /// Report body of the quoted enclave
+    pub fn report_body(&self) -> &'a ReportBody {
+        self.body
+    }
+
+    /// EPID signature (empty if the quote was taken from an IAS report)
+    pub fn signature(&self) -> &'a [u8] {
+        self.signature
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn encode(tail: &[u8]) -> std::vec::Vec<u8> {
+        let mut bytes = std::vec![0u8; EpidQuote::BODY_END];
+        bytes[0] = 2;
+        bytes[2] = 1;
+        bytes[4..8].copy_from_slice(&[0xb, 0xa, 0, 0]);
+        bytes[8] = 7;
+        bytes[10] = 9;
+        bytes[16..48].copy_from_slice(&[0xbb; 32]);
+        bytes[48 + 64..48 + 96].copy_from_slice(&[0xee; 32]);
+        bytes.extend_from_slice(tail);
+        bytes
+    }
+
+    #[test]
+    fn parse() {
+        let bytes = encode(&[3, 0, 0, 0, 1, 2, 3]);
+        let quote = EpidQuote::try_from(&bytes[..]).unwrap();
+        assert_eq!(quote.version(), 2);
+        assert_eq!(quote.sign_type(), EpidSignType::Linkable);
+        assert_eq!(quote.epid_group_id(), [0xb, 0xa, 0, 0]);
+        assert_eq!(quote.qe_svn(), 7);
+        assert_eq!(quote.pce_svn(), 9);
+        assert_eq!(quote.xeid(), 0);
+        assert_eq!(quote.basename(), &[0xbb; 32]);
+        assert_eq!(quote.report_body().mrenclave, [0xee; 32]);
+        assert_eq!(quote.signature(), &[1, 2, 3]);
+
+        let bytes = encode(&[]);
+        let quote = EpidQuote::try_from(&bytes[..]).unwrap();
+        assert!(quote.signature().is_empty());
+    }
+
+    #[test]
+    fn invalid() {
+        let bytes = encode(&[]);
+        assert_eq!(
+            EpidQuote::try_from(&bytes[..431]).unwrap_err(),
+            QuoteError::InvalidLength
+        );
+
+        let bytes = encode(&[4, 0, 0, 0, 1, 2, 3]);
+        assert_eq!(
+            EpidQuote::try_from(&bytes[..]).unwrap_err(),
+            QuoteError::InvalidLength
+        );
+
+        let mut bytes = encode(&[]);
+        bytes[0] = 3;
+        assert_eq!(
+            EpidQuote::try_from(&bytes[..]).unwrap_err(),
+            QuoteError::UnsupportedVersion(3)
+        );
+
+        let mut bytes = encode(&[]);
+        bytes[2] = 2;
+        assert_eq!(
+            EpidQuote::try_from(&bytes[..]).unwrap_err(),
+            QuoteError::UnknownSignType(2)
+        );
+    }
+}
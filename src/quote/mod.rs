@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Attestation quotes
+//!
+//! The types in this module are typically used by a verifier. They parse
+//! quotes in place from a byte slice and never allocate.
+
+mod ecdsa;
+mod epid;
+
+pub use ecdsa::{IssuerChain, PckChain, PpidInfo, QuoteRef, QuoteSignature};
+
+#[allow(deprecated)]
+pub use epid::{EpidQuote, EpidSignType};
+
+/// Error codes for quote parsing
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum QuoteError {
+    /// The input is too short or its length disagrees with an embedded size.
+    InvalidLength,
+    /// The quote version is not supported by this parser.
+    UnsupportedVersion(u16),
+    /// The TEE type of a version 4 quote is not supported.
+    UnsupportedTeeType(u32),
+    /// The signature (or attestation key) type is unknown.
+    UnknownSignType(u16),
+    /// The certification data type is not supported.
+    UnsupportedCertDataType(u16),
+    /// The certification data is malformed.
+    InvalidCertData,
+    /// The quoting enclave is not Intel's.
+    UnknownQeVendor,
+}
+
+impl core::fmt::Display for QuoteError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            QuoteError::InvalidLength => write!(f, "Quote: invalid length"),
+            QuoteError::UnsupportedVersion(v) => write!(f, "Quote: unsupported version {v}"),
+            QuoteError::UnsupportedTeeType(t) => write!(f, "Quote: unsupported TEE type {t:#x}"),
+            QuoteError::UnknownSignType(t) => write!(f, "Quote: unknown signature type {t}"),
+            QuoteError::UnsupportedCertDataType(t) => {
+                write!(f, "Quote: unsupported certification data type {t}")
+            }
+            QuoteError::InvalidCertData => write!(f, "Quote: invalid certification data"),
+            QuoteError::UnknownQeVendor => write!(f, "Quote: non-Intel QE vendor ID"),
+        }
+    }
+}
@@ -4,7 +4,10 @@
 
 use core::{intrinsics::transmute, mem::size_of};
 
-use crate::parameters::{Attributes, Features, MiscSelect, Xfrm};
+use crate::crypto::{Cmac, Digest};
+use crate::hex::Hex;
+use crate::parameters::{Attributes, CetAttributes, Features, MiscSelect, Parameters, Xfrm};
+use crate::signature::Signature;
 
 /// The enclave report body.
 ///
@@ -17,21 +20,44 @@ use crate::parameters::{Attributes, Features, MiscSelect, Xfrm};
 /// [Intel® 64 and IA-32 Architectures Software Developer's Manual Volume 3 (3A, 3B, 3C & 3D): System Programming Guide](https://www.intel.com/content/www/us/en/architecture-and-technology/64-ia-32-architectures-software-developer-vol-3d-part-4-manual.html)
 ///
 /// Table 38-21. Layout of REPORT
-#[derive(Clone, Debug)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct ReportBody {
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     pub cpusvn: [u8; 16],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     miscselect: [u8; 4],
-    reserved1: [u8; 28],
+    cet_attributes: u8,
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    reserved1: [u8; 11],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    isv_ext_prodid: [u8; 16],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     features: [u8; 8],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     xfrm: [u8; 8],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     pub mrenclave: [u8; 32],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     reserved2: [u8; 32],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     pub mrsigner: [u8; 32],
-    reserved3: [u8; 96],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    reserved3: [u8; 32],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    configid: [u8; 64],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     isv_prodid: [u8; 2],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     isv_svn: [u8; 2],
-    reserved4: [u8; 60],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    configsvn: [u8; 2],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    reserved4: [u8; 42],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    isv_family_id: [u8; 16],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     pub reportdata: [u8; 64],
 }
 
@@ -63,6 +89,26 @@ impl AsRef<[u8]> for ReportBody {
     }
 }
 
+impl core::fmt::Debug for ReportBody {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ReportBody")
+            .field("cpusvn", &Hex(&self.cpusvn))
+            .field("miscselect", &self.misc_select())
+            .field("cet_attributes", &self.cet_attributes())
+            .field("isv_ext_prodid", &Hex(&self.isv_ext_prodid))
+            .field("attributes", &self.attributes())
+            .field("mrenclave", &Hex(&self.mrenclave))
+            .field("mrsigner", &Hex(&self.mrsigner))
+            .field("configid", &Hex(&self.configid))
+            .field("isv_prodid", &self.enclave_product_id())
+            .field("isv_svn", &self.enclave_security_version())
+            .field("configsvn", &self.config_svn())
+            .field("isv_family_id", &Hex(&self.isv_family_id))
+            .field("reportdata", &Hex(&self.reportdata))
+            .finish()
+    }
+}
+
 impl ReportBody {
     /// Bit vector specifying which extended features are saved to the MISC region of the
     /// SSA frame when an AEX occurs.
@@ -83,6 +129,11 @@ impl ReportBody {
         Attributes::new(features, xfrm)
     }
 
+    /// CET features of the enclave (requires CET).
+    pub fn cet_attributes(&self) -> CetAttributes {
+        CetAttributes::from_bits_truncate(self.cet_attributes)
+    }
+
     /// ISV assigned Product ID of the enclave.
     pub fn enclave_product_id(&self) -> u16 {
         u16::from_le_bytes(self.isv_prodid)
@@ -92,6 +143,26 @@ impl ReportBody {
     pub fn enclave_security_version(&self) -> u16 {
         u16::from_le_bytes(self.isv_svn)
     }
+
+    /// ISV assigned extended Product ID of the enclave (requires KSS).
+    pub fn enclave_extended_product_id(&self) -> [u8; 16] {
+        self.isv_ext_prodid
+    }
+
+    /// ISV assigned Family ID of the enclave (requires KSS).
+    pub fn enclave_family_id(&self) -> [u8; 16] {
+        self.isv_family_id
+    }
+
+    /// Value provided by the loader at enclave creation (requires KSS).
+    pub fn config_id(&self) -> [u8; 64] {
+        self.configid
+    }
+
+    /// SVN of the `config_id()` value (requires KSS).
+    pub fn config_svn(&self) -> u16 {
+        u16::from_le_bytes(self.configsvn)
+    }
 }
 
 /// The REPORT structure is the output of the EREPORT instruction, and must be 512-Byte aligned.
@@ -101,33 +172,384 @@ impl ReportBody {
 /// [Intel® 64 and IA-32 Architectures Software Developer's Manual Volume 3 (3A, 3B, 3C & 3D): System Programming Guide](https://www.intel.com/content/www/us/en/architecture-and-technology/64-ia-32-architectures-software-developer-vol-3d-part-4-manual.html)
 ///
 /// Table 38-21. Layout of REPORT
-#[derive(Clone, Debug)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C, align(512))]
 pub struct Report {
     pub body: ReportBody,
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     pub keyid: [u8; 32],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     pub mac: [u8; 16],
 }
 
+impl core::fmt::Debug for Report {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Report")
+            .field("body", &self.body)
+            .field("keyid", &Hex(&self.keyid))
+            .field("mac", &Hex(&self.mac))
+            .finish()
+    }
+}
+
+impl Report {
+    /// Creates a report for the target enclave using `ENCLU[EREPORT]`.
+    ///
+    /// This MUST be called from inside an enclave. The `data` is copied into
+    /// the `reportdata` field of the resulting report body.
+    #[inline]
+    #[cfg(target_arch = "x86_64")]
+    pub fn for_target(target: &TargetInfo, data: &[u8; 64]) -> Report {
+        // EREPORT requires REPORTDATA to be 128-byte aligned.
+        #[repr(C, align(128))]
+        struct ReportData([u8; 64]);
+
+        let data = ReportData(*data);
+        let mut report = core::mem::MaybeUninit::<Report>::uninit();
+
+        unsafe {
+            core::arch::asm!(
+                "xchg       {RBX}, rbx",
+                "enclu",
+                "mov        rbx, {RBX}",
+
+                RBX = inout(reg) target => _,
+                in("rax") crate::enclu::EREPORT,
+                in("rcx") &data,
+                in("rdx") report.as_mut_ptr(),
+            );
+
+            report.assume_init()
+        }
+    }
+
+    /// Verifies the report MAC using the report key of the target enclave.
+    ///
+    /// Inside an enclave, the report key is obtained with EGETKEY. This
+    /// function accepts it directly so that local attestation flows can be
+    /// simulated in software (i.e. in tests) without SGX hardware.
+    pub fn verify_for_target<C: Cmac>(&self, report_key: &[u8; 16]) -> Result<bool, C::Error> {
+        C::new(report_key)?.verify(self.body.as_ref(), &self.mac)
+    }
+
+    /// Verifies a report targeted at the current enclave.
+    ///
+    /// This derives the report key using `ENCLU[EGETKEY]` and checks the MAC
+    /// in constant time. It MUST be called from inside the enclave that the
+    /// report was created for (see `Report::for_target()`).
+    #[inline]
+    #[cfg(target_arch = "x86_64")]
+    pub fn verify<C: Cmac>(&self) -> Result<bool, ReportVerifyError<C::Error>> {
+        use crate::key::{KeyName, KeyPolicy, KeyRequest};
+
+        let mut request = KeyRequest::new(KeyName::Report, KeyPolicy::empty());
+        request.keyid = self.keyid;
+
+        let key = request.get_key().map_err(ReportVerifyError::GetKey)?;
+        self.verify_for_target::<C>(&key)
+            .map_err(ReportVerifyError::Cmac)
+    }
+}
+
+/// Error codes for `Report::verify()`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReportVerifyError<E> {
+    /// The report key could not be derived.
+    GetKey(crate::key::GetKeyError),
+    /// The MAC could not be computed.
+    Cmac(E),
+}
+
+/// A `reportdata` value binding a public key and a nonce
+///
+/// The first 32 bytes are the hash of `pubkey || nonce` and the remaining
+/// bytes are zero. This is the construction the quoting enclave uses to bind
+/// its attestation key (with the QE authentication data as the nonce). An
+/// application enclave can use it in the same way to bind its own key to a
+/// verifier's challenge.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ReportData([u8; 64]);
+
+impl From<ReportData> for [u8; 64] {
+    fn from(value: ReportData) -> Self {
+        value.0
+    }
+}
+
+impl AsRef<[u8; 64]> for ReportData {
+    fn as_ref(&self) -> &[u8; 64] {
+        &self.0
+    }
+}
+
+impl ReportData {
+    /// Computes the binding of `pubkey` and `nonce`
+    ///
+    /// Use SHA2-256 to interoperate with the quoting enclave.
+    pub fn bind_pubkey_and_nonce<D: Digest<Output = [u8; 32]>>(
+        pubkey: &[u8],
+        nonce: &[u8],
+    ) -> Self {
+        let mut data = [0; 64];
+        data[..32].copy_from_slice(&D::new().chain(pubkey).chain(nonce).finish());
+        Self(data)
+    }
+
+    /// Checks in constant time that `body` carries this binding
+    pub fn matches(&self, body: &ReportBody) -> bool {
+        let diff = self.0.iter().zip(&body.reportdata);
+        diff.fold(0, |acc, (l, r)| acc | (l ^ r)) == 0
+    }
+}
+
+/// The target of a local attestation report
+///
+/// This structure identifies the enclave that will verify a `Report`. It is
+/// consumed by `Report::for_target()`.
+///
+/// For more information see:
+///
+/// [Intel® 64 and IA-32 Architectures Software Developer's Manual Volume 3 (3A, 3B, 3C & 3D): System Programming Guide](https://www.intel.com/content/www/us/en/architecture-and-technology/64-ia-32-architectures-software-developer-vol-3d-part-4-manual.html)
+///
+/// Table 38-22. Layout of TARGETINFO Data Structure
+#[derive(Copy, Clone)]
+#[repr(C, align(512))]
+pub struct TargetInfo {
+    mrenclave: [u8; 32],
+    attributes: Attributes,
+    cet_attributes: CetAttributes,
+    reserved0: u8,
+    configsvn: u16,
+    miscselect: MiscSelect,
+    reserved1: [u8; 8],
+    configid: [u8; 64],
+    reserved2: [u8; 384],
+}
+
+impl core::fmt::Debug for TargetInfo {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TargetInfo")
+            .field("mrenclave", &Hex(&self.mrenclave))
+            .field("attributes", &self.attributes)
+            .field("cet_attributes", &self.cet_attributes)
+            .field("configsvn", &self.configsvn)
+            .field("miscselect", &self.miscselect)
+            .field("configid", &Hex(&self.configid))
+            .finish()
+    }
+}
+
+impl TargetInfo {
+    /// Creates a new target from the identity of the target enclave.
+    pub const fn new(mrenclave: [u8; 32], attributes: Attributes, miscselect: MiscSelect) -> Self {
+        Self {
+            mrenclave,
+            attributes,
+            cet_attributes: CetAttributes::empty(),
+            reserved0: 0,
+            configsvn: 0,
+            miscselect,
+            reserved1: [0; 8],
+            configid: [0; 64],
+            reserved2: [0; 384],
+        }
+    }
+
+    /// Creates a target for the enclave that produced `report`.
+    ///
+    /// All identity fields are copied verbatim, so a report targeted with the
+    /// result can be verified by that enclave.
+    pub fn from_report(report: &Report) -> Self {
+        let body = &report.body;
+        let features = Features::from_bits_retain(u64::from_le_bytes(body.features));
+        let xfrm = Xfrm::from_bits_retain(u64::from_le_bytes(body.xfrm));
+        let miscselect = MiscSelect::from_bits_retain(u32::from_le_bytes(body.miscselect));
+
+        Self {
+            cet_attributes: CetAttributes::from_bits_retain(body.cet_attributes),
+            configsvn: body.config_svn(),
+            configid: body.configid,
+            ..Self::new(body.mrenclave, Attributes::new(features, xfrm), miscselect)
+        }
+    }
+
+    /// Creates a target for an enclave that has not produced a report yet.
+    ///
+    /// The `parameters` must be the ones passed to `Parameters::secs()` when
+    /// the target enclave was created, i.e. their masks must contain the
+    /// features supported by the platform. Since the target has been
+    /// initialized by `EINIT`, `Features::INIT` is set in the result even
+    /// though it is cleared in those `parameters`.
+    pub fn from_signature(signature: &Signature, parameters: &Parameters) -> Self {
+        let attributes = (parameters.attr.data & parameters.attr.mask) | Features::INIT;
+        let miscselect = parameters.misc.data & parameters.misc.mask;
+
+        Self {
+            cet_attributes: parameters.cet.data & parameters.cet.mask,
+            configsvn: parameters.configsvn,
+            configid: parameters.configid,
+            ..Self::new(signature.body().mrenclave(), attributes, miscselect)
+        }
+    }
+
+    /// Measurement of the target enclave
+    pub const fn mrenclave(&self) -> [u8; 32] {
+        self.mrenclave
+    }
+
+    /// Attributes of the target enclave
+    pub const fn attributes(&self) -> Attributes {
+        self.attributes
+    }
+
+    /// Miscellaneous SSA data selector of the target enclave
+    pub const fn misc_select(&self) -> MiscSelect {
+        self.miscselect
+    }
+}
+
+/// The MAC structure of a `Report2`
+///
+/// This is the `REPORTMACSTRUCT` shared by SGX (`EVERIFYREPORT2`) and TDX
+/// attestation. The MAC covers the first 128 bytes of this structure; the
+/// hashes bind it to the `tee_tcb_info` and `tee_info` of the `Report2`.
+///
+/// For more information see:
+///
+/// [Intel® Trust Domain Extensions (Intel® TDX) Module Base Architecture Specification](https://www.intel.com/content/www/us/en/developer/tools/trust-domain-extensions/documentation.html)
+///
+/// Table: REPORTMACSTRUCT Definition
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C, align(256))]
+pub struct ReportMac {
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    pub report_type: [u8; 4],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    reserved0: [u8; 12],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    pub cpusvn: [u8; 16],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    pub tee_tcb_info_hash: [u8; 48],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    pub tee_info_hash: [u8; 48],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    pub reportdata: [u8; 64],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    reserved1: [u8; 32],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    pub mac: [u8; 32],
+}
+
+// SAFETY: This is safe because all bit patterns are valid for `ReportMac`.
+impl From<[u8; size_of::<ReportMac>()]> for ReportMac {
+    fn from(value: [u8; size_of::<ReportMac>()]) -> Self {
+        unsafe { transmute(value) }
+    }
+}
+
+// SAFETY: This is safe because `ReportMac` has no padding.
+impl From<ReportMac> for [u8; size_of::<ReportMac>()] {
+    fn from(value: ReportMac) -> Self {
+        unsafe { transmute(value) }
+    }
+}
+
+// SAFETY: This is safe because `ReportMac` has no padding.
+impl AsRef<[u8]> for ReportMac {
+    fn as_ref(&self) -> &[u8] {
+        unsafe { transmute::<&Self, &[u8; size_of::<Self>()]>(self) }
+    }
+}
+
+impl core::fmt::Debug for ReportMac {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ReportMac")
+            .field("report_type", &Hex(&self.report_type))
+            .field("cpusvn", &Hex(&self.cpusvn))
+            .field("tee_tcb_info_hash", &Hex(&self.tee_tcb_info_hash))
+            .field("tee_info_hash", &Hex(&self.tee_info_hash))
+            .field("reportdata", &Hex(&self.reportdata))
+            .field("mac", &Hex(&self.mac))
+            .finish()
+    }
+}
+
+/// The second generation report structure
+///
+/// This is the `TDREPORT_STRUCT` layout used by TDX and by SGX platforms that
+/// support `EVERIFYREPORT2`. The `tee_tcb_info` and `tee_info` fields are
+/// opaque to this crate.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C, align(1024))]
+pub struct Report2 {
+    pub mac: ReportMac,
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    pub tee_tcb_info: [u8; 239],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    reserved: [u8; 17],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    pub tee_info: [u8; 512],
+}
+
+// SAFETY: This is safe because all bit patterns are valid for `Report2`.
+impl From<[u8; size_of::<Report2>()]> for Report2 {
+    fn from(value: [u8; size_of::<Report2>()]) -> Self {
+        unsafe { transmute(value) }
+    }
+}
+
+// SAFETY: This is safe because `Report2` has no padding.
+impl From<Report2> for [u8; size_of::<Report2>()] {
+    fn from(value: Report2) -> Self {
+        unsafe { transmute(value) }
+    }
+}
+
+// SAFETY: This is safe because `Report2` has no padding.
+impl AsRef<[u8]> for Report2 {
+    fn as_ref(&self) -> &[u8] {
+        unsafe { transmute::<&Self, &[u8; size_of::<Self>()]>(self) }
+    }
+}
+
+impl core::fmt::Debug for Report2 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Report2")
+            .field("mac", &self.mac)
+            .field("tee_tcb_info", &Hex(&self.tee_tcb_info))
+            .field("tee_info", &Hex(&self.tee_info))
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::{Report, ReportBody};
+    use super::{Report, Report2, ReportBody, ReportMac, TargetInfo};
     use testaso::testaso;
 
     testaso! {
         struct ReportBody: 1, 384 => {
             cpusvn: 0,
             miscselect: 16,
-            reserved1: 20,
+            cet_attributes: 20,
+            reserved1: 21,
+            isv_ext_prodid: 32,
             features: 48,
             xfrm: 56,
             mrenclave: 64,
             reserved2: 96,
             mrsigner: 128,
             reserved3: 160,
+            configid: 192,
             isv_prodid: 256,
             isv_svn: 258,
-            reserved4: 260,
+            configsvn: 260,
+            reserved4: 262,
+            isv_family_id: 304,
             reportdata: 320
         }
 
@@ -136,5 +558,158 @@ mod test {
             keyid: 384,
             mac: 416
         }
+
+        struct ReportMac: 256, 256 => {
+            report_type: 0,
+            reserved0: 4,
+            cpusvn: 16,
+            tee_tcb_info_hash: 32,
+            tee_info_hash: 80,
+            reportdata: 128,
+            reserved1: 192,
+            mac: 224
+        }
+
+        struct Report2: 1024, 1024 => {
+            mac: 0,
+            tee_tcb_info: 256,
+            reserved: 495,
+            tee_info: 512
+        }
+
+        struct TargetInfo: 512, 512 => {
+            mrenclave: 0,
+            attributes: 32,
+            cet_attributes: 48,
+            reserved0: 49,
+            configsvn: 50,
+            miscselect: 52,
+            reserved1: 56,
+            configid: 64,
+            reserved2: 128
+        }
+    }
+
+    #[test]
+    fn kss() {
+        let mut bytes = [0u8; 384];
+        bytes[32..48].copy_from_slice(&[0xe1; 16]);
+        bytes[192..256].copy_from_slice(&[0xc1; 64]);
+        bytes[260..262].copy_from_slice(&7u16.to_le_bytes());
+        bytes[304..320].copy_from_slice(&[0xf1; 16]);
+
+        let body = ReportBody::from(bytes);
+        assert_eq!(body.enclave_extended_product_id(), [0xe1; 16]);
+        assert_eq!(body.config_id(), [0xc1; 64]);
+        assert_eq!(body.config_svn(), 7);
+        assert_eq!(body.enclave_family_id(), [0xf1; 16]);
+    }
+
+    #[cfg(feature = "rcrypto")]
+    #[test]
+    fn verify_for_target() {
+        use crate::crypto::{rcrypto::Aes128Cmac, Cmac};
+
+        const KEY: [u8; 16] = [0x42; 16];
+
+        let body = ReportBody::from([0x5a; 384]);
+        let mac = Aes128Cmac::new(&KEY).unwrap().mac(body.as_ref()).unwrap();
+        let mut report = Report {
+            body,
+            keyid: [0; 32],
+            mac,
+        };
+
+        assert!(report.verify_for_target::<Aes128Cmac>(&KEY).unwrap());
+        assert!(!report.verify_for_target::<Aes128Cmac>(&[0; 16]).unwrap());
+
+        report.body.reportdata[0] ^= 1;
+        assert!(!report.verify_for_target::<Aes128Cmac>(&KEY).unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let report = Report {
+            body: ReportBody::from([0x5a; 384]),
+            keyid: [0x11; 32],
+            mac: [0x22; 16],
+        };
+
+        let json = serde_json::to_string(&report).unwrap();
+        let other: Report = serde_json::from_str(&json).unwrap();
+        assert_eq!(other.body.as_ref(), report.body.as_ref());
+        assert_eq!(other.keyid, report.keyid);
+        assert_eq!(other.mac, report.mac);
+    }
+
+    #[test]
+    fn target_info() {
+        use crate::parameters::{Features, MiscSelect, Parameters};
+        use crate::signature::Signature;
+
+        let mut bytes = [0u8; 384];
+        bytes[16] = 1;
+        bytes[48] = 0x04;
+        bytes[64..96].copy_from_slice(&[0xee; 32]);
+        bytes[192..256].copy_from_slice(&[0xc1; 64]);
+        bytes[260] = 7;
+
+        let report = Report {
+            body: ReportBody::from(bytes),
+            keyid: [0; 32],
+            mac: [0; 16],
+        };
+
+        let target = TargetInfo::from_report(&report);
+        assert_eq!(target.mrenclave(), [0xee; 32]);
+        assert_eq!(target.attributes().features(), Features::MODE64BIT);
+        assert_eq!(target.misc_select(), MiscSelect::EXINFO);
+        assert_eq!(target.configid, [0xc1; 64]);
+        assert_eq!(target.configsvn, 7);
+
+        const SIG: &[u8; 1808] = include_bytes!("../tests/encl.ss");
+        let sig = Signature::from(*SIG);
+        let parameters = Parameters {
+            configsvn: 3,
+            ..sig.body().parameters()
+        };
+
+        let target = TargetInfo::from_signature(&sig, &parameters);
+        assert_eq!(target.mrenclave(), sig.body().mrenclave());
+        assert_eq!(
+            target.attributes(),
+            (parameters.attr.data & parameters.attr.mask) | Features::INIT
+        );
+        assert!(target.attributes().features().contains(Features::INIT));
+        assert!(!parameters.attr.data.features().contains(Features::INIT));
+        assert_eq!(target.configsvn, 3);
+    }
+
+    #[cfg(feature = "rcrypto")]
+    #[test]
+    fn report_data() {
+        use super::ReportData;
+        use crate::crypto::rcrypto::S256Digest;
+
+        // SHA2-256("abc")
+        const ABC: [u8; 32] = [
+            0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae,
+            0x22, 0x23, 0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61,
+            0xf2, 0x00, 0x15, 0xad,
+        ];
+
+        let data = ReportData::bind_pubkey_and_nonce::<S256Digest>(b"ab", b"c");
+        assert_eq!(data.as_ref()[..32], ABC);
+        assert_eq!(data.as_ref()[32..], [0; 32]);
+
+        let mut body = ReportBody::from([0; 384]);
+        assert!(!data.matches(&body));
+
+        body.reportdata = data.into();
+        assert!(data.matches(&body));
+
+        let other = ReportData::bind_pubkey_and_nonce::<S256Digest>(b"ab", b"d");
+        assert!(!other.matches(&body));
     }
 }
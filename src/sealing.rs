@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Data sealing
+//!
+//! The types in this module are typically used by an enclave to derive keys
+//! for persisting secrets outside of the enclave. The derived key is meant to
+//! be used with an `Aead` implementation from the `crypto` module.
+//!
+//! A sealing key is bound to the identity of the enclave and to the security
+//! versions it was sealed at. The `KeyRequest` of a `SealingKey` contains no
+//! secrets and must be stored next to the sealed data so that the same key
+//! can be derived again later.
+
+use crate::key::{KeyName, KeyPolicy, KeyRequest};
+use crate::parameters::{Attributes, Features, Xfrm};
+use crate::ReportBody;
+
+#[cfg(target_arch = "x86_64")]
+use crate::key::GetKeyError;
+
+/// A sealing key derivation policy
+#[derive(Copy, Clone, Debug)]
+pub struct SealingKey(KeyRequest);
+
+impl From<KeyRequest> for SealingKey {
+    fn from(request: KeyRequest) -> Self {
+        Self(request)
+    }
+}
+
+impl SealingKey {
+    // Debug enclaves can never unseal data sealed by production enclaves.
+    const ATTRIBUTE_MASK: Attributes =
+        Attributes::new(Features::INIT.union(Features::DEBUG), Xfrm::empty());
+
+    fn new(policy: KeyPolicy, report: &ReportBody, keyid: [u8; 32]) -> Self {
+        let mut request = KeyRequest::new(KeyName::Seal, policy);
+        request.isvsvn = report.enclave_security_version();
+        request.cpusvn = report.cpusvn;
+        request.attribute_mask = Self::ATTRIBUTE_MASK;
+        request.keyid = keyid;
+        Self(request)
+    }
+
+    /// A key only available to enclaves with the same measurement
+    ///
+    /// The key is bound to the CPUSVN and ISVSVN found in `report`, which
+    /// should be a report of the current enclave. The `keyid` should be
+    /// random for every sealed object.
+    pub fn mrenclave_policy(report: &ReportBody, keyid: [u8; 32]) -> Self {
+        Self::new(KeyPolicy::MRENCLAVE, report, keyid)
+    }
+
+    /// A key available to all enclaves from the same signer and product
+    ///
+    /// The key is bound to the CPUSVN and ISVSVN found in `report`, which
+    /// should be a report of the current enclave. Enclaves of this signer
+    /// with a lower ISVSVN cannot derive this key. The `keyid` should be
+    /// random for every sealed object.
+    pub fn mrsigner_policy(report: &ReportBody, keyid: [u8; 32]) -> Self {
+        Self::new(KeyPolicy::MRSIGNER, report, keyid)
+    }
+
+    /// The key request, which must be stored alongside the sealed data
+    ///
+    /// It can be stored with `<[u8; 512]>::from()` and restored with
+    /// `KeyRequest::try_from()`.
+    pub fn request(&self) -> &KeyRequest {
+        &self.0
+    }
+
+    /// Derives the sealing key using `ENCLU[EGETKEY]`.
+    ///
+    /// This MUST be called from inside an enclave.
+    #[inline]
+    #[cfg(target_arch = "x86_64")]
+    pub fn derive(&self) -> Result<[u8; 16], GetKeyError> {
+        self.0.get_key()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn policies() {
+        let mut bytes = [0u8; 384];
+        bytes[..16].copy_from_slice(&[7; 16]);
+        bytes[258] = 3;
+        let report = ReportBody::from(bytes);
+
+        let key = SealingKey::mrsigner_policy(&report, [1; 32]);
+        let request = key.request();
+        assert_eq!(request.name, KeyName::Seal);
+        assert_eq!(request.policy, KeyPolicy::MRSIGNER);
+        assert_eq!(request.isvsvn, 3);
+        assert_eq!(request.cpusvn, [7; 16]);
+        assert_eq!(request.keyid, [1; 32]);
+        assert_eq!(
+            request.attribute_mask.features(),
+            Features::INIT | Features::DEBUG
+        );
+
+        let key = SealingKey::mrenclave_policy(&report, [2; 32]);
+        assert_eq!(key.request().policy, KeyPolicy::MRENCLAVE);
+        assert_eq!(key.request().keyid, [2; 32]);
+    }
+}
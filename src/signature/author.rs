@@ -1,5 +1,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
+/// Error codes for `Author::validate()`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AuthorError {
+    /// The `HEADER` field does not contain the architectural constant.
+    InvalidHeader1,
+    /// The `HEADER2` field does not contain the architectural constant.
+    InvalidHeader2,
+    /// The `VENDOR` field is neither 0 nor `0x8086`.
+    InvalidVendor(u32),
+}
+
 /// The `Author` of an enclave
 ///
 /// This structure encompasses the first block of fields from `SIGSTRUCT`
@@ -7,10 +19,13 @@
 /// in order to make it easy to hash the fields for the signature.
 #[repr(C)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Author {
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     header1: [u8; 16],
     vendor: u32,
     date: u32,
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     header2: [u8; 16],
     swdefined: u32,
     reserved: [u32; 21],
@@ -19,6 +34,8 @@ pub struct Author {
 impl Author {
     const HEADER1: [u8; 16] = 0x06000000E10000000000010000000000u128.to_be_bytes();
     const HEADER2: [u8; 16] = 0x01010000600000006000000001000000u128.to_be_bytes();
+    const VENDOR_ISV: u32 = 0;
+    const VENDOR_INTEL: u32 = 0x8086;
 
     #[allow(clippy::unreadable_literal)]
     /// Creates a new Author from a date and software defined value.
@@ -36,6 +53,30 @@ impl Author {
         }
     }
 
+    /// Checks the fixed fields of a (possibly foreign) `SIGSTRUCT`
+    ///
+    /// `EINIT` rejects a signature whose headers differ from the
+    /// architectural constants or whose vendor is neither 0 nor Intel.
+    pub fn validate(&self) -> Result<(), AuthorError> {
+        if self.header1 != Self::HEADER1 {
+            return Err(AuthorError::InvalidHeader1);
+        }
+
+        if self.header2 != Self::HEADER2 {
+            return Err(AuthorError::InvalidHeader2);
+        }
+
+        match self.vendor {
+            Self::VENDOR_ISV | Self::VENDOR_INTEL => Ok(()),
+            vendor => Err(AuthorError::InvalidVendor(vendor)),
+        }
+    }
+
+    #[inline]
+    pub fn vendor(&self) -> u32 {
+        self.vendor
+    }
+
     #[inline]
     pub fn date(&self) -> u32 {
         self.date
@@ -49,7 +90,7 @@ impl Author {
 
 #[cfg(test)]
 mod test {
-    use super::Author;
+    use super::{Author, AuthorError};
     use testaso::testaso;
 
     testaso! {
@@ -65,12 +106,30 @@ mod test {
 
     #[test]
     fn author_instantiation() {
-        let author = Author::new(0x2000_03_30, 0u32);
+        let author = Author::new(0x2000_0330, 0u32);
         assert_eq!(author.header1, Author::HEADER1);
         assert_eq!(author.vendor, 0u32);
-        assert_eq!(author.date, 0x2000_03_30);
+        assert_eq!(author.date, 0x2000_0330);
         assert_eq!(author.header2, Author::HEADER2);
         assert_eq!(author.swdefined, 0u32);
         assert_eq!(author.reserved, [0; 21]);
     }
+
+    #[test]
+    fn validate() {
+        let mut author = Author::new(0x2000_0330, 0u32);
+        assert_eq!(author.validate(), Ok(()));
+
+        author.vendor = 0x8086;
+        assert_eq!(author.validate(), Ok(()));
+
+        author.vendor = 1;
+        assert_eq!(author.validate(), Err(AuthorError::InvalidVendor(1)));
+
+        author.header2[0] ^= 1;
+        assert_eq!(author.validate(), Err(AuthorError::InvalidHeader2));
+
+        author.header1[0] ^= 1;
+        assert_eq!(author.validate(), Err(AuthorError::InvalidHeader1));
+    }
 }
@@ -1,6 +1,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::parameters::{Attributes, Masked, MiscSelect, Parameters};
+use super::Author;
+use crate::crypto::Digest;
+use crate::hex::Hex;
+use crate::parameters::{Attributes, CetAttributes, Masked, MiscSelect, Parameters};
 
 impl Parameters {
     /// Creates a signature body
@@ -14,9 +17,9 @@ impl Parameters {
     pub fn body(&self, mrenclave: [u8; 32]) -> Body {
         Body {
             misc: self.misc,
-            cet_attr: Masked { data: 0, mask: 0 },
+            cet_attr: self.cet,
             reserved0: [0; 2],
-            ext_fid: [0; 16],
+            ext_fid: self.ext_fid,
             attr: self.attr,
             mrenclave,
             reserved1: [0; 16],
@@ -34,14 +37,20 @@ impl Parameters {
 /// in order to make it easy to hash the fields for the signature.
 #[repr(C)]
 #[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Body {
     misc: Masked<MiscSelect>,
-    cet_attr: Masked<u8>,
+    cet_attr: Masked<CetAttributes>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     reserved0: [u8; 2],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     ext_fid: [u8; 16],
     attr: Masked<Attributes>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     mrenclave: [u8; 32],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     reserved1: [u8; 16],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     ext_pid: [u8; 16],
     pid: u16,
     svn: u16,
@@ -51,9 +60,10 @@ impl core::fmt::Debug for Body {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("Body")
             .field("misc", &self.misc)
+            .field("cet_attr", &self.cet_attr)
             //.field("reserved0", &self.reserved0)
             .field("attr", &self.attr)
-            .field("mrenclave", &self.mrenclave)
+            .field("mrenclave", &Hex(&self.mrenclave))
             //.field("reserved1", &self.reserved1)
             .field("pid", &self.pid)
             .field("svn", &self.svn)
@@ -67,15 +77,31 @@ impl Body {
         self.mrenclave
     }
 
+    /// Get the digest to be signed with the enclave signing key
+    ///
+    /// This is the hash over the `author` fields followed by the fields of
+    /// this body, exactly as they are laid out in `SIGSTRUCT`. With SHA2-256
+    /// it is the value an external signer needs to produce a `Signature`
+    /// (see `Signature::from_parts()`).
+    pub fn signing_digest<D: Digest>(&self, author: &Author) -> D::Output {
+        let (a, b) = super::signed_bytes(author, self);
+        D::new().chain(&a).chain(&b).finish()
+    }
+
     /// Get the enclave parameters
+    ///
+    /// The `configid`, `configsvn` and `cet_legacy_bitmap` values are not
+    /// part of the signature and are therefore always zero.
     pub fn parameters(&self) -> Parameters {
         Parameters {
             pid: self.pid,
             svn: self.svn,
             misc: self.misc,
             attr: self.attr,
+            cet: self.cet_attr,
             ext_pid: self.ext_pid,
             ext_fid: self.ext_fid,
+            ..Parameters::DEFAULT
         }
     }
 }
@@ -12,12 +12,15 @@
 mod author;
 mod body;
 mod hasher;
+mod token;
 
-pub use author::Author;
+pub use author::{Author, AuthorError};
 pub use body::Body;
-pub use hasher::{Hasher, InvalidSize};
+pub use hasher::{Hasher, InvalidSize, Observer};
+pub use token::EinitToken;
 
-use crate::crypto::PrivateKey;
+use crate::crypto::{Digest, PrivateKey, PublicKey, SigData};
+use crate::hex::Hex;
 
 /// A signature on an enclave
 ///
@@ -27,28 +30,111 @@ use crate::crypto::PrivateKey;
 /// subordinate structures (`Author` and `Body`) for ease during
 /// signature generation and validation.
 #[repr(C)]
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Signature {
     author: Author,
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     modulus: [u8; 384],
     exponent: u32,
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     signature: [u8; 384],
     body: Body,
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     reserved: [u8; 12],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     q1: [u8; 384],
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     q2: [u8; 384],
 }
 
+impl core::fmt::Debug for Signature {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Signature")
+            .field("author", &self.author)
+            .field("modulus", &Hex(&self.modulus))
+            .field("exponent", &self.exponent)
+            .field("signature", &Hex(&self.signature))
+            .field("body", &self.body)
+            .field("q1", &Hex(&self.q1))
+            .field("q2", &Hex(&self.q2))
+            .finish()
+    }
+}
+
+// SAFETY: This is safe because all bit patterns are valid for `Signature`.
+impl From<[u8; core::mem::size_of::<Signature>()]> for Signature {
+    fn from(value: [u8; core::mem::size_of::<Signature>()]) -> Self {
+        unsafe { core::mem::transmute(value) }
+    }
+}
+
+// SAFETY: This is safe because `Signature` has no padding.
+impl From<Signature> for [u8; core::mem::size_of::<Signature>()] {
+    fn from(value: Signature) -> Self {
+        unsafe { core::mem::transmute(value) }
+    }
+}
+
+// The signed bytes of the `author` and `body` sections.
+//
+// SAFETY: This is safe because `Author` and `Body` have no padding.
+fn signed_bytes(
+    author: &Author,
+    body: &Body,
+) -> (
+    [u8; core::mem::size_of::<Author>()],
+    [u8; core::mem::size_of::<Body>()],
+) {
+    unsafe { (core::mem::transmute(*author), core::mem::transmute(*body)) }
+}
+
 impl Signature {
     /// Signs the supplied `author` and `body` with the specified `key`.
     pub fn new<T: PrivateKey>(key: &T, author: Author, body: Body) -> Result<Self, T::Error> {
-        use core::mem::{size_of, transmute};
-
-        let a: [u8; size_of::<Author>()] = unsafe { transmute(author) };
-        let b: [u8; size_of::<Body>()] = unsafe { transmute(body) };
+        let (a, b) = signed_bytes(&author, &body);
         let sd = key.sign(&a, &b)?;
+        Ok(Self::with_sig_data(author, body, sd))
+    }
+
+    /// Assembles a signature from an externally produced RSA signature.
+    ///
+    /// This enables signing in two phases: first, hand the digest from
+    /// `Body::signing_digest()` to an external signer (e.g. an HSM). Then,
+    /// combine its output with the public key using this function, which
+    /// computes the remaining `q1` and `q2` values.
+    ///
+    /// Note that `modulus` and `signature` are little-endian, like all other
+    /// `SIGSTRUCT` values. No validation of the signature is performed.
+    pub fn from_parts<T: PublicKey>(
+        author: Author,
+        body: Body,
+        modulus: &[u8; 384],
+        exponent: u32,
+        signature: &[u8; 384],
+    ) -> Result<Self, T::Error> {
+        let sd = T::from_parts(modulus, exponent)?.sig_data(signature)?;
+        Ok(Self::with_sig_data(author, body, sd))
+    }
+
+    /// Validates the signature without hardware
+    ///
+    /// This checks the RSA signature over the `Author` and `Body` against the
+    /// embedded public key and recomputes `q1` and `q2`. It does not check the
+    /// `Author` header or any policy (such as the key exponent or `MRSIGNER`).
+    pub fn verify<T: PublicKey>(&self) -> Result<bool, T::Error> {
+        let (a, b) = signed_bytes(&self.author, &self.body);
 
-        Ok(Self {
+        let key = T::from_parts(&self.modulus, self.exponent)?;
+        if !key.verify(&a, &b, &self.signature)? {
+            return Ok(false);
+        }
+
+        key.verify_q1_q2(&self.signature, &self.q1, &self.q2)
+    }
+
+    fn with_sig_data(author: Author, body: Body, sd: SigData) -> Self {
+        Self {
             author,
             modulus: sd.modulus,
             exponent: sd.exponent,
@@ -57,7 +143,7 @@ impl Signature {
             reserved: [0; 12],
             q1: sd.q1,
             q2: sd.q2,
-        })
+        }
     }
 
     pub fn author(&self) -> Author {
@@ -67,6 +153,28 @@ impl Signature {
     pub fn body(&self) -> Body {
         self.body
     }
+
+    /// The little-endian modulus of the signing key
+    pub fn modulus(&self) -> &[u8; 384] {
+        &self.modulus
+    }
+
+    /// The exponent of the signing key
+    pub fn exponent(&self) -> u32 {
+        self.exponent
+    }
+
+    /// The little-endian RSA signature
+    pub fn signature(&self) -> &[u8; 384] {
+        &self.signature
+    }
+
+    /// Computes the `MRSIGNER` value for the signing key
+    ///
+    /// With SHA2-256, the result can be compared to `ReportBody::mrsigner`.
+    pub fn mrsigner<D: Digest>(&self) -> D::Output {
+        D::new().chain(&self.modulus).finish()
+    }
 }
 
 #[cfg(test)]
@@ -86,4 +194,14 @@ mod test {
             q2: 1424
         }
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        const SIG: &[u8; core::mem::size_of::<Signature>()] = include_bytes!("../../tests/encl.ss");
+
+        let sig = Signature::from(*SIG);
+        let json = serde_json::to_string(&sig).unwrap();
+        assert_eq!(serde_json::from_str::<Signature>(&json).unwrap(), sig);
+    }
 }
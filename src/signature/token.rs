@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::crypto::Cmac;
+use crate::hex::Hex;
+use crate::parameters::{Attributes, Features, MiscSelect, Xfrm};
+
+/// A launch token
+///
+/// This structure encompasses the `EINITTOKEN` structure from the SGX
+/// documentation. On platforms without flexible launch control, `EINIT`
+/// requires a token produced by the launch enclave, which MACs it with the
+/// key derived for `KeyName::EinitToken`.
+///
+/// For more information see:
+///
+/// [Intel® 64 and IA-32 Architectures Software Developer's Manual Volume 3 (3A, 3B, 3C & 3D): System Programming Guide](https://www.intel.com/content/www/us/en/architecture-and-technology/64-ia-32-architectures-software-developer-vol-3d-part-4-manual.html)
+///
+/// Table 38-23. Layout of EINIT Token (EINITTOKEN)
+#[derive(Copy, Clone)]
+#[repr(C, align(512))]
+pub struct EinitToken {
+    valid: u32,
+    reserved0: [u8; 44],
+    pub attributes: Attributes,
+    pub mrenclave: [u8; 32],
+    reserved1: [u8; 32],
+    pub mrsigner: [u8; 32],
+    reserved2: [u8; 32],
+    pub cpusvn_le: [u8; 16],
+    pub isv_prodid_le: u16,
+    pub isv_svn_le: u16,
+    reserved3: [u8; 24],
+    pub misc_select_le: MiscSelect,
+    pub attributes_le: Attributes,
+    pub keyid: [u8; 32],
+    pub mac: [u8; 16],
+}
+
+impl core::fmt::Debug for EinitToken {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("EinitToken")
+            .field("valid", &self.is_valid())
+            .field("attributes", &self.attributes)
+            .field("mrenclave", &Hex(&self.mrenclave))
+            .field("mrsigner", &Hex(&self.mrsigner))
+            .field("cpusvn_le", &Hex(&self.cpusvn_le))
+            .field("isv_prodid_le", &self.isv_prodid_le)
+            .field("isv_svn_le", &self.isv_svn_le)
+            .field("misc_select_le", &self.misc_select_le)
+            .field("attributes_le", &self.attributes_le)
+            .field("keyid", &Hex(&self.keyid))
+            .field("mac", &Hex(&self.mac))
+            .finish()
+    }
+}
+
+impl From<&[u8; EinitToken::SIZE]> for EinitToken {
+    fn from(value: &[u8; EinitToken::SIZE]) -> Self {
+        let mut token = Self::INVALID;
+        token.as_mut()[..].copy_from_slice(value);
+        token
+    }
+}
+
+// SAFETY: This is safe because the first `SIZE` bytes contain no padding.
+impl AsRef<[u8; EinitToken::SIZE]> for EinitToken {
+    fn as_ref(&self) -> &[u8; EinitToken::SIZE] {
+        unsafe { &*(self as *const Self as *const [u8; Self::SIZE]) }
+    }
+}
+
+// SAFETY: This is safe because all bit patterns are valid for `EinitToken`.
+impl AsMut<[u8; EinitToken::SIZE]> for EinitToken {
+    fn as_mut(&mut self) -> &mut [u8; EinitToken::SIZE] {
+        unsafe { &mut *(self as *mut Self as *mut [u8; Self::SIZE]) }
+    }
+}
+
+impl EinitToken {
+    /// The architectural size of the token in bytes
+    pub const SIZE: usize = 304;
+
+    /// An invalid (all zero) token
+    ///
+    /// `EINIT` accepts such a token only if the enclave's `MRSIGNER` matches
+    /// `IA32_SGXLEPUBKEYHASH`, which is how the launch enclave is started.
+    pub const INVALID: Self = Self {
+        valid: 0,
+        reserved0: [0; 44],
+        attributes: Attributes::new(Features::empty(), Xfrm::empty()),
+        mrenclave: [0; 32],
+        reserved1: [0; 32],
+        mrsigner: [0; 32],
+        reserved2: [0; 32],
+        cpusvn_le: [0; 16],
+        isv_prodid_le: 0,
+        isv_svn_le: 0,
+        reserved3: [0; 24],
+        misc_select_le: MiscSelect::empty(),
+        attributes_le: Attributes::new(Features::empty(), Xfrm::empty()),
+        keyid: [0; 32],
+        mac: [0; 16],
+    };
+
+    /// Whether the token is marked as valid
+    pub fn is_valid(&self) -> bool {
+        self.valid & 1 != 0
+    }
+
+    /// Verifies the token MAC using the launch key.
+    ///
+    /// The MAC covers the first 192 bytes of the token. Inside the launch
+    /// enclave the key is obtained with EGETKEY; this function accepts it
+    /// directly so tokens can be checked in software.
+    pub fn verify<C: Cmac>(&self, launch_key: &[u8; 16]) -> Result<bool, C::Error> {
+        C::new(launch_key)?.verify(&self.as_ref()[..192], &self.mac)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::EinitToken;
+    use testaso::testaso;
+
+    testaso! {
+        struct EinitToken: 512, 512 => {
+            valid: 0,
+            reserved0: 4,
+            attributes: 48,
+            mrenclave: 64,
+            reserved1: 96,
+            mrsigner: 128,
+            reserved2: 160,
+            cpusvn_le: 192,
+            isv_prodid_le: 208,
+            isv_svn_le: 210,
+            reserved3: 212,
+            misc_select_le: 236,
+            attributes_le: 240,
+            keyid: 256,
+            mac: 288
+        }
+    }
+
+    #[test]
+    fn bytes() {
+        let mut bytes = [0u8; EinitToken::SIZE];
+        bytes[0] = 1;
+        bytes[64..96].copy_from_slice(&[0xee; 32]);
+        bytes[288..].copy_from_slice(&[0x11; 16]);
+
+        let token = EinitToken::from(&bytes);
+        assert!(token.is_valid());
+        assert_eq!(token.mrenclave, [0xee; 32]);
+        assert_eq!(token.mac, [0x11; 16]);
+        assert_eq!(token.as_ref(), &bytes);
+        assert!(!EinitToken::INVALID.is_valid());
+    }
+
+    #[cfg(feature = "rcrypto")]
+    #[test]
+    fn verify() {
+        use crate::crypto::{rcrypto::Aes128Cmac, Cmac};
+
+        const KEY: [u8; 16] = [0x42; 16];
+
+        let mut token = EinitToken::INVALID;
+        token.mrenclave = [0xee; 32];
+        token.mac = Aes128Cmac::new(&KEY)
+            .unwrap()
+            .mac(&token.as_ref()[..192])
+            .unwrap();
+        assert!(token.verify::<Aes128Cmac>(&KEY).unwrap());
+
+        // Fields after the first 192 bytes are not covered by the MAC.
+        token.keyid = [1; 32];
+        assert!(token.verify::<Aes128Cmac>(&KEY).unwrap());
+
+        token.mrsigner = [1; 32];
+        assert!(!token.verify::<Aes128Cmac>(&KEY).unwrap());
+    }
+}
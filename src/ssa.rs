@@ -11,11 +11,30 @@
 
 #![allow(clippy::unreadable_literal)]
 
-use core::mem::{size_of_val, transmute};
+use core::mem::{size_of, size_of_val, transmute};
 
 pub use x86_64::structures::idt::ExceptionVector as Vector;
 pub use xsave::XSave;
 
+use core::num::NonZeroU32;
+
+use crate::parameters::{MiscSelect, Xfrm};
+
+/// XSAVE state components in the standard format: (XFRM bit, offset, size)
+///
+/// These are the values reported by CPUID leaf 0DH on Intel processors.
+const XSAVE_COMPONENTS: [(u32, usize, usize); 9] = [
+    (2, 576, 256),    // AVX
+    (3, 960, 64),     // MPX BNDREGS
+    (4, 1024, 64),    // MPX BNDCSR
+    (5, 1088, 64),    // AVX-512 opmask
+    (6, 1152, 512),   // AVX-512 ZMM_Hi256
+    (7, 1664, 1024),  // AVX-512 Hi16_ZMM
+    (9, 2688, 8),     // PKRU
+    (17, 2752, 64),   // AMX XTILECFG
+    (18, 2816, 8192), // AMX XTILEDATA
+];
+
 /// Section 38.9.1.1, Table 38-9
 #[non_exhaustive]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -113,6 +132,204 @@ impl<T> StateSaveArea<T> {
             _ => Some(unsafe { transmute(self.gpr.exitinfo as u8) }),
         }
     }
+
+    #[inline]
+    fn xsave_bytes(&self) -> &[u8; size_of::<XSave>()] {
+        // SAFETY: `XSave` is plain data.
+        let xsave = &self.xsave as *const XSave as *const [u8; size_of::<XSave>()];
+        unsafe { &*xsave }
+    }
+
+    #[inline]
+    fn xsave_bytes_mut(&mut self) -> &mut [u8; size_of::<XSave>()] {
+        // SAFETY: `XSave` is plain data and all bit patterns are valid.
+        let xsave = &mut self.xsave as *mut XSave as *mut [u8; size_of::<XSave>()];
+        unsafe { &mut *xsave }
+    }
+
+    #[inline]
+    fn xsave_u64(&self, offset: usize) -> u64 {
+        let bytes = &self.xsave_bytes()[offset..][..8];
+        u64::from_le_bytes(bytes.try_into().unwrap())
+    }
+
+    /// The legacy (`FXSAVE`) region of the XSAVE area
+    ///
+    /// This holds the x87 and SSE state (Section 13.4.1 of the SDM).
+    #[inline]
+    pub fn xsave_legacy(&self) -> &[u8; 512] {
+        self.xsave_bytes()[..512].try_into().unwrap()
+    }
+
+    /// The legacy (`FXSAVE`) region of the XSAVE area, for patching
+    #[inline]
+    pub fn xsave_legacy_mut(&mut self) -> &mut [u8; 512] {
+        (&mut self.xsave_bytes_mut()[..512]).try_into().unwrap()
+    }
+
+    /// A state component of the XSAVE area
+    ///
+    /// The `offset` and `size` of component `i` are reported in EBX and EAX
+    /// of `CPUID.(EAX=0DH, ECX=i)` for the standard (non-compacted) format.
+    /// Returns `None` if the component does not fit in the XSAVE area.
+    #[inline]
+    pub fn xsave_component(&self, offset: usize, size: usize) -> Option<&[u8]> {
+        self.xsave_bytes().get(offset..offset.checked_add(size)?)
+    }
+
+    /// A state component of the XSAVE area, for patching
+    ///
+    /// See `xsave_component()` for details. If a component is in its initial
+    /// configuration (see `xstate_bv()`), set its bit in `XSTATE_BV` after
+    /// modifying it, or the change will be ignored when the state is restored.
+    #[inline]
+    pub fn xsave_component_mut(&mut self, offset: usize, size: usize) -> Option<&mut [u8]> {
+        let end = offset.checked_add(size)?;
+        self.xsave_bytes_mut().get_mut(offset..end)
+    }
+
+    /// The upper halves of YMM0-YMM15 (the AVX state component)
+    ///
+    /// Returns `None` unless AVX state was saved in the standard format, at
+    /// its architectural offset.
+    #[inline]
+    pub fn ymm_hi128(&self) -> Option<&[u8; 256]> {
+        if !self.xstate_bv().contains(Xfrm::AVX) || self.xcomp_bv() & 1 << 63 != 0 {
+            return None;
+        }
+
+        self.xsave_component(576, 256)?.try_into().ok()
+    }
+
+    /// The x87 FPU control word
+    #[inline]
+    pub fn fcw(&self) -> u16 {
+        let bytes = &self.xsave_bytes()[..2];
+        u16::from_le_bytes(bytes.try_into().unwrap())
+    }
+
+    /// The SSE control and status register
+    #[inline]
+    pub fn mxcsr(&self) -> u32 {
+        let bytes = &self.xsave_bytes()[24..28];
+        u32::from_le_bytes(bytes.try_into().unwrap())
+    }
+
+    /// The `XSTATE_BV` field of the XSAVE header
+    ///
+    /// This is the set of state components that were not in their initial
+    /// configuration when the state was saved. It is a subset of the XFRM
+    /// of the enclave.
+    #[inline]
+    pub fn xstate_bv(&self) -> Xfrm {
+        Xfrm::from_bits_retain(self.xsave_u64(512))
+    }
+
+    /// The `XCOMP_BV` field of the XSAVE header
+    ///
+    /// Bit 63 indicates that the area uses the compacted format.
+    #[inline]
+    pub fn xcomp_bv(&self) -> u64 {
+        self.xsave_u64(520)
+    }
+}
+
+/// An exception handler
+///
+/// The handler may modify the saved state, for example to emulate the
+/// faulting instruction and advance `gpr.rip`. It returns `true` if the
+/// enclave may resume from the saved state.
+///
+/// Handlers must not panic. This crate is `no_std` and cannot contain an
+/// unwind, so a panicking handler unwinds straight out of
+/// `Handlers::handle()` into the enclave entry point, leaving the SSA frame
+/// in whatever state the handler left it. Enclaves are normally built with
+/// `panic = "abort"`, which turns this into an abort. Report unrecoverable
+/// exceptions by returning `false` instead.
+pub struct Handler<T = [u8; 824]>(pub fn(&mut StateSaveArea<T>) -> bool);
+
+impl<T> Clone for Handler<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handler<T> {}
+
+/// Exception handlers registered per vector
+///
+/// When an enclave is entered with CSSA > 0, its entry point can pass the
+/// previous SSA frame to `Handlers::handle()`. If this returns `true`, the
+/// enclave exits and the host resumes it with `ERESUME`. Otherwise, the
+/// exception should be treated as fatal.
+///
+/// Since the constructors are `const`, the handlers can be kept in a `static`.
+pub struct Handlers<T = [u8; 824]>([Option<Handler<T>>; 32]);
+
+impl<T> Default for Handlers<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Handlers<T> {
+    /// Creates an empty set of handlers
+    pub const fn new() -> Self {
+        Self([None; 32])
+    }
+
+    /// Registers `handler` for exceptions with the given `vector`
+    pub const fn with(mut self, vector: Vector, handler: Handler<T>) -> Self {
+        self.0[vector as usize] = Some(handler);
+        self
+    }
+
+    /// Dispatches the exception saved in `ssa` to its handler
+    ///
+    /// Returns `false` if the SSA frame contains no valid exception or no
+    /// handler is registered for its vector. If the handler panics, the panic
+    /// is propagated to the caller (see `Handler`).
+    pub fn handle(&self, ssa: &mut StateSaveArea<T>) -> bool {
+        match ssa.vector().and_then(|v| self.0[v as usize]) {
+            Some(handler) => (handler.0)(ssa),
+            None => false,
+        }
+    }
+}
+
+/// The size of the XSAVE area for the enabled state components
+///
+/// This is the size of the standard (non-compacted) format that the CPU
+/// uses in the SSA. Components unknown to this function are ignored.
+pub fn xsave_size(xfrm: Xfrm) -> usize {
+    XSAVE_COMPONENTS
+        .iter()
+        .filter(|(bit, ..)| xfrm.bits() & (1 << bit) != 0)
+        .map(|(_, offset, size)| offset + size)
+        .fold(576, usize::max)
+}
+
+/// The number of pages in each SSA frame (`SECS.SSAFRAMESIZE`)
+///
+/// Pass this to `Parameters::secs()` and `Hasher::new()` when building an
+/// enclave with the given XFRM and MISCSELECT.
+pub fn frame_pages(xfrm: Xfrm, misc: MiscSelect) -> NonZeroU32 {
+    let misc = match misc.contains(MiscSelect::EXINFO) {
+        true => size_of::<Misc>(),
+        false => 0,
+    };
+
+    let size = xsave_size(xfrm).max(size_of::<XSave>()) + misc + size_of::<GenPurposeRegs>();
+    NonZeroU32::new(((size + 4095) / 4096) as u32).unwrap()
+}
+
+/// The size of `extra` in a `StateSaveArea` for the given XFRM and MISCSELECT
+///
+/// An enclave declares its SSA frames as `StateSaveArea<[u8; N]>` with this
+/// value as `N`. With the default of 824 bytes, a frame fits in one page.
+pub fn extra_size(xfrm: Xfrm, misc: MiscSelect) -> usize {
+    let pages = frame_pages(xfrm, misc).get() as usize;
+    pages * 4096 - size_of::<XSave>() - size_of::<Misc>() - size_of::<GenPurposeRegs>()
 }
 
 #[cfg(test)]
@@ -165,4 +382,101 @@ mod test {
             gpr: 3912
         }
     }
+
+    #[test]
+    fn xsave() {
+        let mut page = [0u8; 4096];
+        page[..2].copy_from_slice(&0x037fu16.to_le_bytes());
+        page[24..28].copy_from_slice(&0x1f80u32.to_le_bytes());
+        page[512..520].copy_from_slice(&0b111u64.to_le_bytes());
+        page[520..528].copy_from_slice(&(1u64 << 63).to_le_bytes());
+
+        let ssa: StateSaveArea = unsafe { transmute(page) };
+        assert_eq!(ssa.fcw(), 0x037f);
+        assert_eq!(ssa.mxcsr(), 0x1f80);
+        assert_eq!(ssa.xstate_bv(), Xfrm::X87 | Xfrm::SSE | Xfrm::AVX);
+        assert_eq!(ssa.xcomp_bv(), 1 << 63);
+        assert_eq!(&ssa.xsave_legacy()[..2], &[0x7f, 0x03]);
+        assert_eq!(ssa.ymm_hi128(), None);
+        assert_eq!(ssa.xsave_component(3000, 100), None);
+        assert_eq!(ssa.xsave_component(usize::MAX, 2), None);
+    }
+
+    #[test]
+    fn ymm() {
+        let mut ssa: StateSaveArea = unsafe { transmute([0u8; 4096]) };
+        assert_eq!(ssa.ymm_hi128(), None);
+
+        ssa.xsave_component_mut(512, 8)
+            .unwrap()
+            .copy_from_slice(&Xfrm::AVX.bits().to_le_bytes());
+        ssa.xsave_component_mut(576, 256).unwrap()[0] = 0xaa;
+        ssa.xsave_legacy_mut()[24] = 0x80;
+
+        assert_eq!(ssa.ymm_hi128().unwrap()[0], 0xaa);
+        assert_eq!(ssa.mxcsr(), 0x80);
+    }
+
+    #[test]
+    fn sizing() {
+        let legacy = Xfrm::X87 | Xfrm::SSE;
+        assert_eq!(xsave_size(legacy), 576);
+        assert_eq!(frame_pages(legacy, MiscSelect::EXINFO).get(), 1);
+        assert_eq!(extra_size(legacy, MiscSelect::EXINFO), 824);
+
+        let avx512 = legacy | Xfrm::AVX | Xfrm::OPMASK | Xfrm::ZMM_HI256 | Xfrm::HI16_ZMM;
+        assert_eq!(xsave_size(avx512), 2688);
+        assert_eq!(xsave_size(avx512 | Xfrm::MPK), 2696);
+        assert_eq!(extra_size(avx512 | Xfrm::MPK, MiscSelect::empty()), 824);
+
+        let amx = legacy | Xfrm::from_bits_retain(0b11 << 17);
+        assert_eq!(xsave_size(amx), 11008);
+        assert_eq!(frame_pages(amx, MiscSelect::empty()).get(), 3);
+        assert_eq!(extra_size(amx, MiscSelect::EXINFO), 824 + 8192);
+    }
+
+    #[test]
+    fn handlers() {
+        fn skip(ssa: &mut StateSaveArea) -> bool {
+            ssa.gpr.rip += 2;
+            true
+        }
+
+        static HANDLERS: Handlers = Handlers::new().with(Vector::InvalidOpcode, Handler(skip));
+
+        let mut ssa: StateSaveArea = unsafe { transmute([0u8; 4096]) };
+        assert!(!HANDLERS.handle(&mut ssa));
+
+        ssa.gpr.exitinfo = StateSaveArea::<[u8; 824]>::VALID | Vector::InvalidOpcode as u32;
+        assert!(HANDLERS.handle(&mut ssa));
+        assert_eq!(ssa.gpr.rip, 2);
+
+        ssa.gpr.exitinfo = StateSaveArea::<[u8; 824]>::VALID | Vector::Page as u32;
+        assert!(!HANDLERS.handle(&mut ssa));
+        assert_eq!(ssa.gpr.rip, 2);
+    }
+
+    #[test]
+    fn handler_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        fn fail(ssa: &mut StateSaveArea) -> bool {
+            ssa.gpr.rip += 2;
+            panic!("handler failed");
+        }
+
+        static HANDLERS: Handlers = Handlers::new().with(Vector::InvalidOpcode, Handler(fail));
+
+        let mut ssa: StateSaveArea = unsafe { transmute([0u8; 4096]) };
+        ssa.gpr.exitinfo = StateSaveArea::<[u8; 824]>::VALID | Vector::InvalidOpcode as u32;
+
+        // The panic is not contained and the partial changes remain.
+        let result = catch_unwind(AssertUnwindSafe(|| HANDLERS.handle(&mut ssa)));
+        assert!(result.is_err());
+        assert_eq!(ssa.gpr.rip, 2);
+
+        // The handlers remain usable afterwards.
+        ssa.gpr.exitinfo = StateSaveArea::<[u8; 824]>::VALID | Vector::Page as u32;
+        assert!(!HANDLERS.handle(&mut ssa));
+    }
 }
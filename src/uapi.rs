@@ -0,0 +1,453 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Linux kernel interface
+//!
+//! The types in this module are typically used by an enclave loader. They
+//! mirror the structures and ioctl numbers from the kernel's
+//! `arch/x86/include/uapi/asm/sgx.h` exactly.
+//!
+//! Pointers are carried as plain `u64` values, just like in the kernel ABI.
+//! Therefore, the constructors below do not track lifetimes: callers must
+//! keep the referenced data alive until the ioctl returns.
+
+use crate::page::{Class, Flags, SecInfo, Secs};
+use crate::signature::Signature;
+
+const fn ioc(dir: u64, nr: u64, size: usize) -> u64 {
+    dir << 30 | (size as u64) << 16 | (MAGIC as u64) << 8 | nr
+}
+
+const NONE: u64 = 0;
+const WRITE: u64 = 1;
+const READ: u64 = 2;
+
+/// The ioctl type of the SGX device
+pub const MAGIC: u8 = 0xA4;
+
+/// The SGX driver providing the devices
+///
+/// Besides the in-tree driver, some distributions still ship Intel's
+/// out-of-tree DCAP driver. Both use the same ioctl numbers and structures
+/// for building an enclave, but expose the devices at different paths.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Driver {
+    /// The in-tree driver (Linux 5.11 and later)
+    InTree,
+    /// The out-of-tree DCAP driver
+    OutOfTree,
+}
+
+impl Driver {
+    /// The path of the enclave device
+    pub const fn enclave_device(self) -> &'static str {
+        match self {
+            Self::InTree => "/dev/sgx_enclave",
+            Self::OutOfTree => "/dev/sgx/enclave",
+        }
+    }
+
+    /// The path of the provisioning device
+    ///
+    /// An open handle of this device is passed in `EnclaveProvision` to
+    /// grant `Features::PROVISIONING_KEY`.
+    pub const fn provision_device(self) -> &'static str {
+        match self {
+            Self::InTree => "/dev/sgx_provision",
+            Self::OutOfTree => "/dev/sgx/provision",
+        }
+    }
+
+    /// Finds the installed driver
+    ///
+    /// The `exists` callback checks whether a device path exists, e.g. with
+    /// `std::path::Path::exists()`. The in-tree driver is preferred if both
+    /// are present.
+    pub fn detect(exists: impl Fn(&str) -> bool) -> Option<Self> {
+        [Self::InTree, Self::OutOfTree]
+            .into_iter()
+            .find(|driver| exists(driver.enclave_device()))
+    }
+}
+
+/// `SGX_IOC_ENCLAVE_CREATE`
+pub const ENCLAVE_CREATE: u64 = ioc(WRITE, 0x00, core::mem::size_of::<EnclaveCreate>());
+/// `SGX_IOC_ENCLAVE_ADD_PAGES`
+pub const ENCLAVE_ADD_PAGES: u64 = ioc(WRITE | READ, 0x01, core::mem::size_of::<EnclaveAddPages>());
+/// `SGX_IOC_ENCLAVE_INIT`
+pub const ENCLAVE_INIT: u64 = ioc(WRITE, 0x02, core::mem::size_of::<EnclaveInit>());
+/// `SGX_IOC_ENCLAVE_PROVISION`
+pub const ENCLAVE_PROVISION: u64 = ioc(WRITE, 0x03, core::mem::size_of::<EnclaveProvision>());
+/// `SGX_IOC_ENCLAVE_SET_ATTRIBUTE` of the out-of-tree driver
+///
+/// This has the same number and argument (`EnclaveProvision`) as
+/// `ENCLAVE_PROVISION`.
+pub const ENCLAVE_SET_ATTRIBUTE: u64 = ENCLAVE_PROVISION;
+/// `SGX_IOC_VEPC_REMOVE_ALL`
+pub const VEPC_REMOVE_ALL: u64 = ioc(NONE, 0x04, 0);
+/// `SGX_IOC_ENCLAVE_RESTRICT_PERMISSIONS`
+pub const ENCLAVE_RESTRICT_PERMISSIONS: u64 = ioc(
+    WRITE | READ,
+    0x05,
+    core::mem::size_of::<EnclaveRestrictPermissions>(),
+);
+/// `SGX_IOC_ENCLAVE_MODIFY_TYPES`
+pub const ENCLAVE_MODIFY_TYPES: u64 = ioc(
+    WRITE | READ,
+    0x06,
+    core::mem::size_of::<EnclaveModifyTypes>(),
+);
+/// `SGX_IOC_ENCLAVE_REMOVE_PAGES`
+pub const ENCLAVE_REMOVE_PAGES: u64 = ioc(
+    WRITE | READ,
+    0x07,
+    core::mem::size_of::<EnclaveRemovePages>(),
+);
+
+// Moves the range past the `count` bytes processed by the kernel and clears
+// `count`. Returns `true` while pages remain.
+fn advance(offset: &mut u64, length: &mut u64, count: &mut u64) -> bool {
+    let done = (*count).min(*length);
+    *offset += done;
+    *length -= done;
+    *count = 0;
+    *length != 0
+}
+
+/// `SGX_PAGE_MEASURE`: measure the added pages with EEXTEND
+pub const PAGE_MEASURE: u64 = 0x01;
+
+/// `struct sgx_enclave_create`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct EnclaveCreate {
+    /// The address of the `Secs`
+    pub src: u64,
+}
+
+impl From<&Secs> for EnclaveCreate {
+    fn from(secs: &Secs) -> Self {
+        Self {
+            src: secs as *const _ as u64,
+        }
+    }
+}
+
+/// `struct sgx_enclave_add_pages`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct EnclaveAddPages {
+    /// The address of the page data
+    pub src: u64,
+    /// The page offset from the enclave base
+    pub offset: u64,
+    /// The length of the page data (a multiple of the page size)
+    pub length: u64,
+    /// The address of the `SecInfo`
+    pub secinfo: u64,
+    /// The page flags (e.g. `PAGE_MEASURE`)
+    pub flags: u64,
+    /// Output: the number of bytes added
+    pub count: u64,
+}
+
+impl EnclaveAddPages {
+    /// Adds `src` at `offset` bytes from the enclave base.
+    pub fn new(src: &[u8], offset: usize, secinfo: &SecInfo, measure: bool) -> Self {
+        Self {
+            src: src.as_ptr() as u64,
+            offset: offset as u64,
+            length: src.len() as u64,
+            secinfo: secinfo as *const _ as u64,
+            flags: if measure { PAGE_MEASURE } else { 0 },
+            count: 0,
+        }
+    }
+}
+
+/// `struct sgx_enclave_init`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct EnclaveInit {
+    /// The address of the `Signature`
+    pub sigstruct: u64,
+}
+
+impl From<&Signature> for EnclaveInit {
+    fn from(signature: &Signature) -> Self {
+        Self {
+            sigstruct: signature as *const _ as u64,
+        }
+    }
+}
+
+/// `struct sgx_enclave_provision`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct EnclaveProvision {
+    /// An open file descriptor of `/dev/sgx_provision`
+    pub fd: u64,
+}
+
+/// `struct sgx_enclave_restrict_permissions`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct EnclaveRestrictPermissions {
+    /// The page offset from the enclave base
+    pub offset: u64,
+    /// The length of the range (a multiple of the page size)
+    pub length: u64,
+    /// The new permission bits of the pages
+    pub permissions: u64,
+    /// Output: the `ENCLU[EMODPR]` error code
+    pub result: u64,
+    /// Output: the number of bytes processed
+    pub count: u64,
+}
+
+impl EnclaveRestrictPermissions {
+    /// Restricts the permissions of a range of enclave pages.
+    ///
+    /// Only the permission bits of `flags` are passed to the kernel.
+    pub fn new(offset: usize, length: usize, flags: Flags) -> Self {
+        let perms = Flags::READ | Flags::WRITE | Flags::EXECUTE;
+
+        Self {
+            offset: offset as u64,
+            length: length as u64,
+            permissions: (flags & perms).bits().into(),
+            result: 0,
+            count: 0,
+        }
+    }
+
+    /// Prepares a retry after the kernel processed only `count` bytes.
+    ///
+    /// This advances `offset` and `length` past the processed pages and
+    /// clears the output fields. Returns `false` once the whole range is done.
+    pub fn resume(&mut self) -> bool {
+        self.result = 0;
+        advance(&mut self.offset, &mut self.length, &mut self.count)
+    }
+}
+
+/// `struct sgx_enclave_modify_types`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct EnclaveModifyTypes {
+    /// The page offset from the enclave base
+    pub offset: u64,
+    /// The length of the range (a multiple of the page size)
+    pub length: u64,
+    /// The new `Class` of the pages
+    pub page_type: u64,
+    /// Output: the `ENCLU[EMODT]` error code
+    pub result: u64,
+    /// Output: the number of bytes processed
+    pub count: u64,
+}
+
+impl EnclaveModifyTypes {
+    /// Changes the class of a range of enclave pages.
+    pub fn new(offset: usize, length: usize, class: Class) -> Self {
+        Self {
+            offset: offset as u64,
+            length: length as u64,
+            page_type: class as u64,
+            result: 0,
+            count: 0,
+        }
+    }
+
+    /// Prepares a retry after the kernel processed only `count` bytes.
+    ///
+    /// This advances `offset` and `length` past the processed pages and
+    /// clears the output fields. Returns `false` once the whole range is done.
+    pub fn resume(&mut self) -> bool {
+        self.result = 0;
+        advance(&mut self.offset, &mut self.length, &mut self.count)
+    }
+}
+
+/// `struct sgx_enclave_remove_pages`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct EnclaveRemovePages {
+    /// The page offset from the enclave base
+    pub offset: u64,
+    /// The length of the range (a multiple of the page size)
+    pub length: u64,
+    /// Output: the number of bytes processed
+    pub count: u64,
+}
+
+impl EnclaveRemovePages {
+    /// Removes a range of enclave pages.
+    pub fn new(offset: usize, length: usize) -> Self {
+        Self {
+            offset: offset as u64,
+            length: length as u64,
+            count: 0,
+        }
+    }
+
+    /// Prepares a retry after the kernel processed only `count` bytes.
+    ///
+    /// This advances `offset` and `length` past the processed pages and
+    /// clears the output fields. Returns `false` once the whole range is done.
+    pub fn resume(&mut self) -> bool {
+        advance(&mut self.offset, &mut self.length, &mut self.count)
+    }
+}
+
+/// `struct sgx_enclave_run`, as used by the `__vdso_sgx_enter_enclave()`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct EnclaveRun {
+    /// The address of the `Tcs` used to enter the enclave
+    pub tcs: u64,
+    /// Output: the last `ENCLU` leaf (`EENTER`, `ERESUME` or `EEXIT`)
+    pub function: u32,
+    /// Output: the vector of the exception
+    pub exception_vector: u16,
+    /// Output: the error code of the exception
+    pub exception_error_code: u16,
+    /// Output: the address that triggered the exception
+    pub exception_addr: u64,
+    /// The address of a callback run on exceptions
+    pub user_handler: u64,
+    /// The data passed to `user_handler`
+    pub user_data: u64,
+    /// Reserved for future extensions
+    pub reserved: [u8; 216],
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use testaso::testaso;
+
+    testaso! {
+        struct EnclaveCreate: 8, 8 => {
+            src: 0
+        }
+
+        struct EnclaveAddPages: 8, 48 => {
+            src: 0,
+            offset: 8,
+            length: 16,
+            secinfo: 24,
+            flags: 32,
+            count: 40
+        }
+
+        struct EnclaveInit: 8, 8 => {
+            sigstruct: 0
+        }
+
+        struct EnclaveProvision: 8, 8 => {
+            fd: 0
+        }
+
+        struct EnclaveRestrictPermissions: 8, 40 => {
+            offset: 0,
+            length: 8,
+            permissions: 16,
+            result: 24,
+            count: 32
+        }
+
+        struct EnclaveModifyTypes: 8, 40 => {
+            offset: 0,
+            length: 8,
+            page_type: 16,
+            result: 24,
+            count: 32
+        }
+
+        struct EnclaveRemovePages: 8, 24 => {
+            offset: 0,
+            length: 8,
+            count: 16
+        }
+
+        struct EnclaveRun: 8, 256 => {
+            tcs: 0,
+            function: 8,
+            exception_vector: 12,
+            exception_error_code: 14,
+            exception_addr: 16,
+            user_handler: 24,
+            user_data: 32,
+            reserved: 40
+        }
+    }
+
+    #[test]
+    fn ioctls() {
+        assert_eq!(ENCLAVE_CREATE, 0x4008_A400);
+        assert_eq!(ENCLAVE_ADD_PAGES, 0xC030_A401);
+        assert_eq!(ENCLAVE_INIT, 0x4008_A402);
+        assert_eq!(ENCLAVE_PROVISION, 0x4008_A403);
+        assert_eq!(VEPC_REMOVE_ALL, 0x0000_A404);
+        assert_eq!(ENCLAVE_RESTRICT_PERMISSIONS, 0xC028_A405);
+        assert_eq!(ENCLAVE_MODIFY_TYPES, 0xC028_A406);
+        assert_eq!(ENCLAVE_REMOVE_PAGES, 0xC018_A407);
+        assert_eq!(ENCLAVE_SET_ATTRIBUTE, 0x4008_A403);
+    }
+
+    #[test]
+    fn driver() {
+        let oot = |path: &str| path.starts_with("/dev/sgx/");
+        assert_eq!(Driver::detect(oot), Some(Driver::OutOfTree));
+        assert_eq!(Driver::detect(|_| true), Some(Driver::InTree));
+        assert_eq!(Driver::detect(|_| false), None);
+
+        assert_eq!(Driver::InTree.enclave_device(), "/dev/sgx_enclave");
+        assert_eq!(Driver::OutOfTree.provision_device(), "/dev/sgx/provision");
+    }
+
+    #[test]
+    fn conversions() {
+        let si = SecInfo::reg_rx();
+        let page = [0u8; 4096];
+        let add = EnclaveAddPages::new(&page, 8192, &si, true);
+        assert_eq!(add.src, page.as_ptr() as u64);
+        assert_eq!(add.offset, 8192);
+        assert_eq!(add.length, 4096);
+        assert_eq!(add.secinfo, &si as *const _ as u64);
+        assert_eq!(add.flags, PAGE_MEASURE);
+
+        let rp = EnclaveRestrictPermissions::new(0, 4096, Flags::READ | Flags::PENDING);
+        assert_eq!(rp.permissions, 1);
+
+        let mt = EnclaveModifyTypes::new(4096, 4096, Class::Trimmed);
+        assert_eq!(mt.page_type, 4);
+    }
+
+    #[test]
+    fn resume() {
+        let mut rp = EnclaveRestrictPermissions::new(4096, 3 * 4096, Flags::READ);
+        rp.count = 4096;
+        rp.result = 1;
+        assert!(rp.resume());
+        assert_eq!(
+            (rp.offset, rp.length, rp.count, rp.result),
+            (8192, 8192, 0, 0)
+        );
+
+        rp.count = 8192;
+        assert!(!rp.resume());
+        assert_eq!((rp.offset, rp.length), (16384, 0));
+
+        let mut mt = EnclaveModifyTypes::new(0, 4096, Class::Trimmed);
+        assert!(mt.resume());
+        mt.count = 4096;
+        assert!(!mt.resume());
+
+        let mut rm = EnclaveRemovePages::new(0, 4096);
+        rm.count = u64::MAX;
+        assert!(!rm.resume());
+        assert_eq!((rm.offset, rm.length), (4096, 0));
+    }
+}